@@ -0,0 +1,64 @@
+// Builds a merkle allowlist from a CSV of `address,allocation` rows.
+//
+//     cargo run --example merkle_tree -- allowlist.csv > allowlist.json
+//
+// The root goes in a phase `merkle_root` (allocation = phase limit) or in the
+// `whitelist_merkle_root` (allocation = whitelist amount), each buyer then sends
+// its entry of `proofs` as `phase_proof` / `whitelist_proof` when depositing.
+
+use std::{ collections::BTreeMap, env, fs, process };
+
+use discoverinj_mintpad::{ merkle::{ leaf_hash, MerkleTree }, msg::AllocationProof };
+use serde_json::json;
+
+fn main() {
+    let path = env::args().nth(1).unwrap_or_else(|| {
+        eprintln!("usage: merkle_tree <allowlist.csv>");
+        process::exit(1);
+    });
+    let csv = fs::read_to_string(&path).unwrap_or_else(|err| {
+        eprintln!("cannot read {}: {}", path, err);
+        process::exit(1);
+    });
+
+    let mut entries = vec![];
+    for (line_number, line) in csv.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let (address, allocation) = line.split_once(',').unwrap_or((line, ""));
+        match allocation.trim().parse::<u64>() {
+            Ok(allocation) => entries.push((address.trim().to_string(), allocation)),
+            // Header row
+            Err(_) if line_number == 0 => {}
+            Err(_) => {
+                eprintln!("invalid allocation on line {}: {}", line_number + 1, line);
+                process::exit(1);
+            }
+        }
+    }
+
+    let tree = MerkleTree::new(
+        entries
+            .iter()
+            .map(|(address, allocation)| leaf_hash(address, *allocation))
+            .collect()
+    );
+    let root = tree.root().unwrap_or_else(|| {
+        eprintln!("{} has no allowlist entries", path);
+        process::exit(1);
+    });
+
+    let proofs: BTreeMap<&str, AllocationProof> = entries
+        .iter()
+        .enumerate()
+        .map(|(index, (address, allocation))| {
+            let proof = tree.proof(index).iter().map(hex::encode).collect();
+            (address.as_str(), AllocationProof { allocation: *allocation, proof })
+        })
+        .collect();
+
+    let output = json!({ "merkle_root": hex::encode(root), "proofs": proofs });
+    println!("{}", serde_json::to_string_pretty(&output).unwrap());
+}
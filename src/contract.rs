@@ -3,47 +3,128 @@ use std::collections::HashMap;
 use cosmwasm_std::{
     attr,
     entry_point,
+    from_json,
     to_json_binary,
+    to_json_vec,
     Addr,
+    BankMsg,
     Binary,
-    Deps,
-    DepsMut,
+    Coin,
+    CosmosMsg,
     Env,
+    Event,
     MessageInfo,
-    Response,
+    Order,
+    Reply,
     StdError,
     StdResult,
+    Storage,
+    SubMsg,
     Uint128,
+    WasmMsg,
 };
+use cw2::{ get_contract_version, set_contract_version, ContractVersion };
+use cw20::{ Cw20ExecuteMsg, Cw20ReceiveMsg, MinterResponse };
+use cw20_base::msg::InstantiateMsg as Cw20InstantiateMsg;
+use cw_storage_plus::Bound;
+use semver::Version;
+use sha2::{ Digest, Sha256 };
 
 use crate::{
+    error::ContractError,
+    injective::{
+        create_denom_message,
+        denom_creation_fee,
+        factory_denom,
+        mint_message,
+        set_metadata_message,
+        InjectiveMsgWrapper,
+        InjectiveQueryWrapper,
+    },
+    migrations::migrate_from_v1_0,
+    merkle::{ parse_hash, verify_proof },
     msg::{
+        AllocationProof,
+        AllUsersResponse,
+        AuthorizationPayload,
+        AllowlistResponse,
+        DepositRejection,
+        EscrowResponse,
         ExecuteMsg,
         InstantiateMsg,
+        FeesResponse,
         LaunchpadResponse,
+        MigrateMsg,
+        PendingAdminResponse,
         PhaseInfoResponse,
+        PriceQuoteResponse,
+        PurchaseAuthorization,
         QueryMsg,
+        ReceiveMsg,
+        RolesResponse,
+        SimulateDepositResponse,
+        StatusResponse,
         UserInfoResponse,
+        UserTokensResponse,
+        WhitelistResponse,
     },
     state::{
+        PendingAdmin,
         Phase,
         PhaseInformation,
+        PhaseMode,
         PhaseResp,
+        Role,
+        RoleHolder,
+        RolloverPolicy,
+        SaleStage,
+        SaleStatus,
+        SaleTokenKind,
         State,
         TokenInfo,
         User,
         UserToken,
+        VestingConfig,
         WhitelistUser,
+        users,
+        PENDING_ADMIN,
+        PHASE_ALLOWLIST,
+        PHASE_ALLOWLIST_GENERATION,
+        PHASE_ALLOWLIST_SIZE,
+        ROLES,
         STATE,
-        USERS,
+        USED_NONCES,
         USER_INFO,
+        WHITELIST,
+    },
+    utils::{
+        allowlist_prefix,
+        current_phase,
+        get_token_amount,
+        is_eligible,
+        transfer_token_message,
+        vested_amount,
+        BASIS_POINTS,
+        MAX_FEES_PERCENTAGE,
     },
-    utils::{ current_phase, get_token_amount, transfer_token_message },
 };
 
 // version info for migration info
-//const CONTRACT_NAME: &str = "Discoverinj-Launchpad";
-//const CONTRACT_VERSION: &str = "1.0";
+const CONTRACT_NAME: &str = "Discoverinj-Launchpad";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+// launchpads deployed before versioning carry no cw2 info
+const LEGACY_CONTRACT_VERSION: &str = "1.0.0";
+
+// pagination info for queries
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+const INSTANTIATE_TOKEN_REPLY_ID: u64 = 1;
+
+// Responses may carry injective tokenfactory messages, and queries reach the injective bindings
+type Response = cosmwasm_std::Response<InjectiveMsgWrapper>;
+type Deps<'a> = cosmwasm_std::Deps<'a, InjectiveQueryWrapper>;
+type DepsMut<'a> = cosmwasm_std::DepsMut<'a, InjectiveQueryWrapper>;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -51,40 +132,145 @@ pub fn instantiate(
     _env: Env,
     _info: MessageInfo,
     msg: InstantiateMsg
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     let state = State {
-        admin: msg.admin,
+        admin: deps.api.addr_validate(&msg.admin)?,
         start_time: msg.start_time,
         end_time: msg.end_time,
-        phases: msg.phases.unwrap_or_else(Vec::new),
+        phases: msg.phases.unwrap_or_default(),
         buy_denom: msg.buy_denom,
         buy_token_type: msg.buy_token_type,
         tokens_per_buy: msg.tokens_per_buy,
         token_info: msg.token_info,
         claimable: false,
-        fees_wallet: msg.fees_wallet,
-        fees_percentage: 0,
-        withdraw_wallet: msg.withdraw_wallet,
-        whitelist: msg.whitelist.unwrap_or_default(),
+        fees_wallet: deps.api.addr_validate(&msg.fees_wallet)?,
+        fees_percentage: msg.fees_percentage.unwrap_or_default(),
+        fees_collected: Uint128::zero(),
+        withdrawn: Uint128::zero(),
+        total_claimed: Uint128::zero(),
+        escrowed: Uint128::zero(),
+        withdraw_wallet: deps.api.addr_validate(&msg.withdraw_wallet)?,
+        soft_cap: msg.soft_cap,
+        vesting: msg.vesting,
+        tge_time: None,
+        whitelist_merkle_root: msg.whitelist_merkle_root,
+        signer_pubkey: msg.signer_pubkey,
+        stage: SaleStage::Open,
+        pause_reason: None,
     };
 
     if msg.start_time > msg.end_time {
-        return Err(StdError::generic_err("Start time must be before end time"));
+        return Err(ContractError::InvalidTimeRange {});
+    }
+
+    if state.fees_percentage > MAX_FEES_PERCENTAGE {
+        return Err(ContractError::InvalidFeesPercentage { max: MAX_FEES_PERCENTAGE });
+    }
+
+    if let Some(vesting) = &state.vesting {
+        validate_vesting(vesting)?;
+    }
+
+    if let Some(root) = &state.whitelist_merkle_root {
+        validate_merkle_root(root)?;
+    }
+
+    for phase in state.phases.iter() {
+        validate_new_phase(phase)?;
+    }
+    validate_supply(&state)?;
+
+    for wl in msg.whitelist.unwrap_or_default() {
+        let address = deps.api.addr_validate(wl.address.as_str())?;
+        WHITELIST.save(deps.storage, address, &wl.amount)?;
     }
 
-    USERS.save(deps.storage, "user_tokens".to_string(), &Vec::new())?;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     STATE.save(deps.storage, &state)?;
 
     Ok(Response::default())
 }
 
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.id {
+        INSTANTIATE_TOKEN_REPLY_ID => {
+            // Record the sale cw20 instantiated on finalize
+            let response = msg.result.into_result().map_err(StdError::generic_err)?;
+            let address = response.events
+                .iter()
+                .filter(|event| event.ty == "instantiate")
+                .flat_map(|event| event.attributes.iter())
+                .find(|attribute| attribute.key == "_contract_address")
+                .ok_or(ContractError::MissingTokenAddress {})?;
+            let address = deps.api.addr_validate(&address.value)?;
+
+            let mut state = STATE.load(deps.storage)?;
+            state.token_info.denom = Some(address.to_string());
+            STATE.save(deps.storage, &state)?;
+
+            Ok(
+                Response::new()
+                    .add_attribute("action", "instantiate_token")
+                    .add_attribute("denom", address.to_string())
+            )
+        }
+        id => Err(ContractError::UnknownReply { id }),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(
+    mut deps: DepsMut,
+    env: Env,
+    _msg: MigrateMsg
+) -> Result<Response, ContractError> {
+    let stored = get_contract_version(deps.storage).unwrap_or_else(|_| ContractVersion {
+        contract: CONTRACT_NAME.to_string(),
+        version: LEGACY_CONTRACT_VERSION.to_string(),
+    });
+
+    if stored.contract != CONTRACT_NAME {
+        return Err(ContractError::InvalidMigration { contract: stored.contract });
+    }
+
+    let from_version = parse_version(&stored.version)?;
+    let to_version = parse_version(CONTRACT_VERSION)?;
+    if from_version > to_version {
+        return Err(ContractError::CannotDowngrade {
+            from: stored.version,
+            to: CONTRACT_VERSION.to_string(),
+        });
+    }
+
+    // Storage layout migrations, applied in order
+    if from_version < Version::new(1, 1, 0) {
+        migrate_from_v1_0(deps.branch(), &env)?;
+    }
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(
+        Response::new()
+            .add_attribute("action", "migrate")
+            .add_attribute("from_version", stored.version)
+            .add_attribute("to_version", CONTRACT_VERSION)
+    )
+}
+
+fn parse_version(version: &str) -> Result<Version, ContractError> {
+    Version::parse(version).map_err(|_| ContractError::InvalidVersion {
+        version: version.to_string(),
+    })
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     msg: ExecuteMsg
-) -> Result<Response, StdError> {
+) -> Result<Response, ContractError> {
     match msg {
         ExecuteMsg::UpdateConfig {
             start_time,
@@ -94,7 +280,10 @@ pub fn execute(
             tokens_per_buy,
             token_info,
             phases,
-            new_admin,
+            soft_cap,
+            vesting,
+            whitelist_merkle_root,
+            signer_pubkey,
         } =>
             update_config(
                 deps,
@@ -107,7 +296,10 @@ pub fn execute(
                 tokens_per_buy,
                 token_info,
                 phases,
-                new_admin
+                soft_cap,
+                vesting,
+                whitelist_merkle_root,
+                signer_pubkey
             ),
         ExecuteMsg::AddPhase { phase } => add_phase(deps, env, info, phase),
         ExecuteMsg::UpdatePhase {
@@ -116,8 +308,8 @@ pub fn execute(
             end_time,
             price_per_token,
             supply,
-            address_list,
             limit,
+            merkle_root,
         } =>
             update_phase(
                 deps,
@@ -128,20 +320,54 @@ pub fn execute(
                 end_time,
                 price_per_token,
                 supply,
-                address_list,
-                limit
+                limit,
+                merkle_root
             ),
         ExecuteMsg::RemovePhase { name } => remove_phase(deps, env, info, name),
         ExecuteMsg::AddWhitelist { whitelist } => add_whitelist(deps, env, info, whitelist),
-        ExecuteMsg::RemoveFromWhitelist { address } =>
-            remove_from_whitelist(deps, env, info, address),
-        ExecuteMsg::Deposit { amount } => deposit(deps, env, info, amount),
+        ExecuteMsg::RemoveFromWhitelist { addresses } =>
+            remove_from_whitelist(deps, env, info, addresses),
+        ExecuteMsg::AddToPhaseAllowlist { phase_name, addresses } =>
+            add_to_phase_allowlist(deps, env, info, phase_name, addresses),
+        ExecuteMsg::RemoveFromPhaseAllowlist { phase_name, addresses } =>
+            remove_from_phase_allowlist(deps, env, info, phase_name, addresses),
+        ExecuteMsg::Deposit { amount, phase_proof, whitelist_proof, authorization } =>
+            deposit(
+                deps,
+                env,
+                info.sender,
+                info.funds,
+                "native",
+                amount,
+                phase_proof,
+                whitelist_proof,
+                authorization
+            ),
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
         ExecuteMsg::FlipClaimStatus {} => flip_claim_status(deps, env, info),
         ExecuteMsg::ClaimTokens {} => claim_token(deps, env, info),
-        ExecuteMsg::Withdraw { denom, token_type } => withdraw(deps, env, info, denom, token_type),
+        ExecuteMsg::ClaimRefund {} => claim_refund(deps, env, info),
+        ExecuteMsg::Settle { address } => settle(deps, env, info, address),
+        ExecuteMsg::Withdraw {} => withdraw(deps, env, info),
+        ExecuteMsg::WithdrawUnsold {} => withdraw_unsold(deps, env, info),
+        ExecuteMsg::RolloverPhases {} => rollover_phases(deps, env),
+        ExecuteMsg::FundSale {} => fund_sale(deps, "native", info.funds),
+        ExecuteMsg::Finalize {} => finalize(deps, env, info),
+        ExecuteMsg::Cancel {} => cancel(deps, env, info),
+        ExecuteMsg::Pause { reason } => set_pause(deps, info, Some(reason)),
+        ExecuteMsg::Unpause {} => set_pause(deps, info, None),
+        ExecuteMsg::PausePhase { name, reason } => set_phase_pause(deps, info, name, Some(reason)),
+        ExecuteMsg::UnpausePhase { name } => set_phase_pause(deps, info, name, None),
+        ExecuteMsg::GrantRole { address, role } => update_role(deps, info, address, role, true),
+        ExecuteMsg::RevokeRole { address, role } => update_role(deps, info, address, role, false),
+        ExecuteMsg::ProposeAdmin { address, expiry } =>
+            propose_admin(deps, env, info, address, expiry),
+        ExecuteMsg::AcceptAdmin {} => accept_admin(deps, env, info),
+        ExecuteMsg::CancelAdminProposal {} => cancel_admin_proposal(deps, info),
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn update_config(
     deps: DepsMut,
     env: Env,
@@ -153,56 +379,110 @@ fn update_config(
     tokens_per_buy: Option<Uint128>,
     token_info: Option<TokenInfo>,
     phases: Option<Vec<Phase>>,
-    new_admin: Option<Addr>
-) -> StdResult<Response> {
+    soft_cap: Option<Uint128>,
+    vesting: Option<VestingConfig>,
+    whitelist_merkle_root: Option<String>,
+    signer_pubkey: Option<Binary>
+) -> Result<Response, ContractError> {
     let mut state = STATE.load(deps.storage)?;
 
-    if state.admin != info.sender {
-        return Err(StdError::generic_err("Unauthorized: not admin"));
+    ensure_role(deps.as_ref(), &state, &info.sender, Role::Owner)?;
+
+    // The schedule and pricing are frozen once the sale started, settlement details stay editable
+    let pricing_update =
+        buy_denom.is_some() ||
+        buy_token_type.is_some() ||
+        tokens_per_buy.is_some() ||
+        phases.is_some() ||
+        soft_cap.is_some();
+    let schedule_update = pricing_update || start_time.is_some() || end_time.is_some();
+    match state.status(&env) {
+        SaleStatus::Draft | SaleStatus::Upcoming => {}
+        // A paused sale can still be rescheduled, or rotate its signer or merkle root
+        SaleStatus::Paused if !pricing_update => {}
+        SaleStatus::Ended if !schedule_update => {}
+        SaleStatus::Finalized | SaleStatus::Claimable if !schedule_update => {}
+        SaleStatus::Active { .. } | SaleStatus::Paused | SaleStatus::Ended => {
+            return Err(ContractError::SaleStarted {});
+        }
+        status => {
+            return Err(invalid_status(status));
+        }
     }
 
-    if env.block.time.seconds() > state.start_time && env.block.time.seconds() < state.end_time {
-        return Err(StdError::generic_err("Unauthorized: launchpad already in progress"));
+    if start_time > end_time {
+        return Err(ContractError::InvalidTimeRange {});
     }
 
-    if start_time > end_time {
-        return Err(StdError::generic_err("Start time must be before end time"));
+    if let Some(buy_denom) = buy_denom {
+        state.buy_denom = buy_denom;
     }
 
-    if buy_denom.is_some() {
-        state.buy_denom = buy_denom.unwrap();
+    if let Some(buy_token_type) = buy_token_type {
+        if buy_token_type == "native" || buy_token_type == "cw20" {
+            state.buy_token_type = buy_token_type;
+        }
+    }
+
+    if let Some(tokens_per_buy) = tokens_per_buy {
+        state.tokens_per_buy = tokens_per_buy;
     }
 
-    if buy_token_type.is_some() {
-        if buy_token_type.clone().unwrap() == "native" || buy_token_type.clone().unwrap() == "cw20" {
-            state.buy_token_type = buy_token_type.unwrap();
+    if let Some(token_info) = token_info {
+        // Claims are computed from the token info once they opened
+        if state.tge_time.is_some() {
+            return Err(ContractError::TokenInfoLocked {});
+        }
+        // The escrowed tokens were counted in the previous denom and decimals
+        let current = &state.token_info;
+        let token_changed =
+            token_info.denom != current.denom ||
+            token_info.kind != current.kind ||
+            token_info.decimals != current.decimals;
+        if token_changed && !state.escrowed.is_zero() {
+            return Err(ContractError::SaleTokenLocked {});
         }
+        state.token_info = token_info;
     }
 
-    if tokens_per_buy.is_some() {
-        state.tokens_per_buy = tokens_per_buy.unwrap();
+    if let Some(start_time) = start_time {
+        state.start_time = start_time;
     }
 
-    if token_info.is_some() {
-        state.token_info = token_info.unwrap();
+    if let Some(end_time) = end_time {
+        state.end_time = end_time;
     }
 
-    if start_time.is_some() {
-        state.start_time = start_time.unwrap();
+    if let Some(phases) = phases {
+        for phase in phases.iter() {
+            validate_new_phase(phase)?;
+        }
+        state.phases = phases;
+    }
+
+    if soft_cap.is_some() {
+        state.soft_cap = soft_cap;
     }
 
-    if end_time.is_some() {
-        state.end_time = end_time.unwrap();
+    if let Some(vesting) = vesting {
+        if state.tge_time.is_some() {
+            return Err(ContractError::VestingStarted {});
+        }
+        validate_vesting(&vesting)?;
+        state.vesting = Some(vesting);
     }
 
-    if phases.is_some() {
-        state.phases = phases.unwrap();
+    if let Some(root) = whitelist_merkle_root {
+        validate_merkle_root(&root)?;
+        state.whitelist_merkle_root = Some(root);
     }
 
-    if new_admin.is_some() {
-        state.admin = new_admin.unwrap();
+    if signer_pubkey.is_some() {
+        state.signer_pubkey = signer_pubkey;
     }
 
+    validate_supply(&state)?;
+
     STATE.save(deps.storage, &state)?;
 
     Ok(Response::new().add_attributes(vec![attr("action", "update_config")]))
@@ -213,40 +493,22 @@ fn add_whitelist(
     env: Env,
     info: MessageInfo,
     whitelist: Vec<WhitelistUser>
-) -> StdResult<Response> {
-    let mut state = STATE.load(deps.storage)?;
-    if state.admin != info.sender {
-        return Err(StdError::generic_err("Unauthorized: not admin"));
-    }
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    ensure_role(deps.as_ref(), &state, &info.sender, Role::WhitelistManager)?;
 
-    // Launchpad start check
-    if state.is_launchpad_started(env) {
-        return Err(StdError::generic_err("Unauthorized: Launchpad started"));
-    }
+    ensure_editable(&state, &env)?;
 
     let mut attributes = vec![];
-    // Iterate over the new whitelist entries
+    // Add new entries, or update the amount of existing ones
     for wl in whitelist {
-        // Check if the address is already in the whitelist
-        let mut found = false;
-        for item in &mut state.whitelist {
-            if item.address == wl.address {
-                // Update the existing entry with the new value
-                item.amount = wl.amount;
-                found = true;
-                break;
-            }
-        }
-
-        // If the address was not found, add it as a new entry
-        if !found {
-            state.whitelist.push(WhitelistUser { address: wl.address.clone(), amount: wl.amount });
-            attributes.push(("address, amount", format!("{} {}", wl.address, wl.amount)));
+        let address = deps.api.addr_validate(wl.address.as_str())?;
+        if !WHITELIST.has(deps.storage, address.clone()) {
+            attributes.push(("address, amount", format!("{} {}", address, wl.amount)));
         }
+        WHITELIST.save(deps.storage, address, &wl.amount)?;
     }
 
-    // Save the updated state
-    STATE.save(deps.storage, &state)?;
     Ok(Response::new().add_attribute("action", "add_whitelist").add_attributes(attributes))
 }
 
@@ -254,240 +516,529 @@ fn remove_from_whitelist(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
-    address: Addr
-) -> StdResult<Response> {
-    let mut state = STATE.load(deps.storage)?;
-    if state.admin != info.sender {
-        return Err(StdError::generic_err("Unauthorized: not admin"));
+    addresses: Vec<String>
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    ensure_role(deps.as_ref(), &state, &info.sender, Role::WhitelistManager)?;
+
+    ensure_editable(&state, &env)?;
+
+    let mut response = Response::new().add_attribute("action", "remove_from_whitelist");
+    for address in addresses {
+        let address = deps.api.addr_validate(&address)?;
+        if WHITELIST.has(deps.storage, address.clone()) {
+            WHITELIST.remove(deps.storage, address.clone());
+            response = response.add_attribute("removed_address", address.to_string());
+        } else {
+            response = response.add_attribute("address_not_found", address.to_string());
+        }
     }
 
-    // Launchpad start check
-    if state.is_launchpad_started(env) {
-        return Err(StdError::generic_err("Unauthorized: Launchpad started"));
-    }
-    // Attempt to remove the address from the whitelist
-    let was_present = state.whitelist.iter().position(|item| item.address == &address);
+    Ok(response)
+}
+
+fn add_to_phase_allowlist(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    phase_name: String,
+    addresses: Vec<String>
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    ensure_role(deps.as_ref(), &state, &info.sender, Role::WhitelistManager)?;
 
-    let response = Response::new().add_attribute("action", "remove_from_whitelist");
+    ensure_editable(&state, &env)?;
 
-    if let Some(index) = was_present {
-        state.whitelist.remove(index);
-        STATE.save(deps.storage, &state)?;
-        Ok(response.add_attribute("removed_address", address.to_string()))
-    } else {
-        Ok(response.add_attribute("address_not_found", address.to_string()))
+    if !state.phases.iter().any(|p| p.name == phase_name) {
+        return Err(ContractError::PhaseNotFound { name: phase_name });
     }
+
+    let (name, generation) = allowlist_prefix(deps.storage, &phase_name)?;
+    let mut added = 0u64;
+    for address in addresses {
+        let key = (name.clone(), generation, deps.api.addr_validate(&address)?);
+        if !PHASE_ALLOWLIST.has(deps.storage, key.clone()) {
+            PHASE_ALLOWLIST.save(deps.storage, key, &())?;
+            added += 1;
+        }
+    }
+    PHASE_ALLOWLIST_SIZE.update(deps.storage, phase_name.clone(), |size| -> StdResult<_> {
+        Ok(size.unwrap_or(0) + added)
+    })?;
+
+    Ok(
+        Response::new()
+            .add_attribute("action", "add_to_phase_allowlist")
+            .add_attribute("phase_name", phase_name)
+            .add_attribute("added", added.to_string())
+    )
 }
 
-fn deposit(deps: DepsMut, env: Env, info: MessageInfo, amount: u64) -> StdResult<Response> {
-    let mut state = STATE.load(deps.storage)?;
-    let current_time = env.block.time.seconds();
+fn remove_from_phase_allowlist(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    phase_name: String,
+    addresses: Vec<String>
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    ensure_role(deps.as_ref(), &state, &info.sender, Role::WhitelistManager)?;
+
+    ensure_editable(&state, &env)?;
 
-    // Check if we're within the launchpad time
-    if !(current_time >= state.start_time && current_time <= state.end_time) {
-        return Err(StdError::generic_err("Not in launchpad time"));
+    let (name, generation) = allowlist_prefix(deps.storage, &phase_name)?;
+    let mut removed = 0u64;
+    for address in addresses {
+        let key = (name.clone(), generation, deps.api.addr_validate(&address)?);
+        if PHASE_ALLOWLIST.has(deps.storage, key.clone()) {
+            PHASE_ALLOWLIST.remove(deps.storage, key);
+            removed += 1;
+        }
     }
+    PHASE_ALLOWLIST_SIZE.update(deps.storage, phase_name.clone(), |size| -> StdResult<_> {
+        Ok(size.unwrap_or(0) - removed)
+    })?;
 
-    // Attempt to load user, or initialize a new one if not found
-    let mut usr = USER_INFO.may_load(deps.storage, info.sender.clone())?.unwrap_or(User {
-        buy_phases: HashMap::new(),
-        amount: 0,
-        tokens: Uint128::zero(),
-        claimed: false,
-    });
+    Ok(
+        Response::new()
+            .add_attribute("action", "remove_from_phase_allowlist")
+            .add_attribute("phase_name", phase_name)
+            .add_attribute("removed", removed.to_string())
+    )
+}
 
-    // Determine the current phase, return an error if not found or not in phase time
-    let mut current_phase = current_phase(current_time, state.phases.clone()).ok_or_else(||
-        StdError::generic_err("Unauthorized: Not in launchpad time")
-    )?;
+fn receive_cw20(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    let sender = deps.api.addr_validate(&cw20_msg.sender)?;
+    let payment = vec![Coin { denom: info.sender.to_string(), amount: cw20_msg.amount }];
+
+    match from_json(&cw20_msg.msg)? {
+        ReceiveMsg::Deposit { amount, phase_proof, whitelist_proof, authorization } => {
+            // Only the configured buy token can pay for a cw20 denominated sale
+            if state.buy_token_type != "cw20" || info.sender != state.buy_denom {
+                return Err(ContractError::InvalidCw20Token {});
+            }
 
-    let previous_phase = get_previous_phase(&current_phase, &state.phases.clone());
-    if previous_phase.is_some() && current_phase.total_minted == 0 {
-        let pr_phase = previous_phase.unwrap();
-        current_phase.supply += pr_phase.supply - pr_phase.total_minted;
-        if current_phase.address_list.len() > 0 {
-            current_phase.address_list.extend(pr_phase.address_list.iter().cloned());
+            deposit(
+                deps,
+                env,
+                sender,
+                payment,
+                "cw20",
+                amount,
+                phase_proof,
+                whitelist_proof,
+                authorization
+            )
         }
+        ReceiveMsg::FundSale {} => fund_sale(deps, "cw20", payment),
+    }
+}
 
-        for phase in state.phases.iter_mut() {
-            if phase.name == current_phase.name {
-                phase.supply += pr_phase.supply - pr_phase.total_minted;
-                if phase.address_list.len() > 0 {
-                    phase.address_list.extend(pr_phase.address_list.iter().cloned());
-                }
-            }
+fn fund_sale(
+    deps: DepsMut,
+    token_type: &str,
+    payment: Vec<Coin>
+) -> Result<Response, ContractError> {
+    let mut state = STATE.load(deps.storage)?;
+    let denom = state.token_info.denom.clone().ok_or(ContractError::TokenNotSettled {})?;
+
+    // Minted sale tokens need no escrow
+    if state.token_info.kind.is_minted() || state.token_info.kind.token_type() != token_type {
+        return Err(ContractError::WrongSaleToken {});
+    }
+    let amount = match payment.as_slice() {
+        [coin] if coin.denom == denom && !coin.amount.is_zero() => coin.amount,
+        _ => {
+            return Err(ContractError::WrongSaleToken {});
         }
+    };
+
+    state.escrowed += amount;
+    STATE.save(deps.storage, &state)?;
+
+    Ok(
+        Response::new()
+            .add_attribute("action", "fund_sale")
+            .add_attribute("amount", amount.to_string())
+            .add_attribute("escrowed", state.escrowed.to_string())
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn deposit(
+    deps: DepsMut,
+    env: Env,
+    sender: Addr,
+    payment: Vec<Coin>,
+    token_type: &str,
+    amount: u64,
+    phase_proof: Option<AllocationProof>,
+    whitelist_proof: Option<AllocationProof>,
+    authorization: Option<PurchaseAuthorization>
+) -> Result<Response, ContractError> {
+    let mut state = STATE.load(deps.storage)?;
+    let current_time = env.block.time.seconds();
+
+    if state.buy_token_type != token_type {
+        return Err(ContractError::WrongTokenType { expected: state.buy_token_type });
     }
 
+    // Ended phases hand over their unsold supply before the current phase is priced, burned
+    // or returned supply is left to `RolloverPhases` and `Finalize`
+    let rollovers = state.apply_rollovers(current_time, None);
+    let response = rollover_response(&state, rollovers)?;
+
+    let plan = plan_deposit(
+        deps.as_ref(),
+        &env,
+        &state,
+        &sender,
+        amount,
+        phase_proof,
+        whitelist_proof,
+        authorization
+    )?;
+
     // Perform checks to validate the deposit
-    validate_deposit(&info, &state, amount, &mut current_phase, &usr)?;
+    validate_deposit(&plan, amount)?;
+    validate_payment(&payment, &state.buy_denom, plan.cost)?;
+
+    if let Some(nonce) = plan.nonce {
+        USED_NONCES.save(deps.storage, nonce, &())?;
+    }
 
     // Record the deposit in the user's account and phase
-    record_deposit(&state, amount, &mut usr, &current_phase)?;
+    let DepositPlan { phase: current_phase, cost, mut usr, .. } = plan;
+    record_deposit(&state, amount, cost, &mut usr, &current_phase)?;
 
-    // if success, update total mint
+    // if success, update total mint and raised funds
     for phase in state.phases.iter_mut() {
         if phase.name == current_phase.name {
             // Directly modify the found phase
-            phase.total_minted += amount;
+            match phase.mode {
+                PhaseMode::FixedPrice |
+                PhaseMode::Tiered { .. } |
+                PhaseMode::BondingCurve { .. } => {
+                    phase.total_minted += amount;
+                    phase.total_raised += cost;
+                }
+                // Pro-rata and auction funds only count as raised once settled
+                PhaseMode::ProRata => {
+                    phase.total_committed += amount;
+                }
+                PhaseMode::DutchAuction { .. } => {
+                    phase.total_minted += amount;
+                    if phase.total_minted == phase.supply {
+                        phase.clearing_price = Some(current_phase.price_at(current_time));
+                    }
+                }
+            }
             break; // Exit the loop once the phase is found and updated
         }
     }
 
-    // Load the existing user tokens from storage
-    let mut user_tokens = USERS.load(deps.storage, "user_tokens".to_string())?;
-
-    let mut found = false;
-    for token in user_tokens.iter_mut() {
-        if token.address == info.sender {
-            token.tokens += usr.tokens;
-            found = true;
-            break;
-        }
-    }
-
-    if !found {
-        user_tokens.push(UserToken {
-            address: info.sender.clone(),
-            tokens: usr.tokens,
-        });
-    }
-
-    USERS.save(deps.storage, "user_tokens".to_string(), &user_tokens)?;
+    // Keep the per-address token index in sync with the user's total
+    users().save(deps.storage, sender.clone(), &(UserToken {
+        address: sender.clone(),
+        tokens: usr.tokens,
+    }))?;
     STATE.save(deps.storage, &state)?;
-    USER_INFO.save(deps.storage, info.sender, &usr)?;
+    USER_INFO.save(deps.storage, sender, &usr)?;
 
     Ok(
-        Response::new()
+        response
             .add_attribute("action", "deposit")
             .add_attribute("current_phase", current_phase.name)
             .add_attribute("amount_bought", amount.to_string())
     )
 }
 
-fn validate_deposit(
-    info: &MessageInfo,
+// What a deposit would buy, shared by deposit and its simulation
+struct DepositPlan {
+    // Current phase with the proven buy limit
+    phase: Phase,
+    cost: Uint128,
+    eligible: bool,
+    wl_user: u64,
+    usr: User,
+    // Signed cap on the phase purchases, whitelist allowances don't lift it
+    authorized_amount: Option<u64>,
+    // Authorization nonce to consume
+    nonce: Option<u64>,
+}
+
+#[allow(clippy::too_many_arguments)]
+fn plan_deposit(
+    deps: Deps,
+    env: &Env,
     state: &State,
+    sender: &Addr,
     amount: u64,
-    current_phase: &mut Phase,
-    usr: &User
-) -> StdResult<()> {
-    if !current_phase.address_list.is_empty() && !current_phase.address_list.contains(&info.sender) {
-        return Err(StdError::generic_err("Unauthorized: Not in this phase"));
+    phase_proof: Option<AllocationProof>,
+    whitelist_proof: Option<AllocationProof>,
+    authorization: Option<PurchaseAuthorization>
+) -> Result<DepositPlan, ContractError> {
+    let current_time = env.block.time.seconds();
+
+    match state.status(env) {
+        SaleStatus::Active { .. } => {}
+        SaleStatus::Draft | SaleStatus::Upcoming => {
+            return Err(ContractError::SaleNotStarted {});
+        }
+        SaleStatus::Paused => {
+            return Err(ContractError::Paused {
+                reason: state.pause_reason.clone().unwrap_or_default(),
+            });
+        }
+        SaleStatus::Cancelled => {
+            return Err(invalid_status(SaleStatus::Cancelled));
+        }
+        _ => {
+            return Err(ContractError::SaleEnded {});
+        }
     }
 
-    // Check phase supply limits
-    if current_phase.total_minted + amount > current_phase.supply {
-        return Err(StdError::generic_err("Invalid amount: overbuy"));
+    // Buyers only commit once the project escrowed what the sale can sell
+    if let Some(required) = state.escrow_required() {
+        if state.escrowed < required {
+            return Err(ContractError::Underfunded { escrowed: state.escrowed, required });
+        }
     }
 
-    let wl_user = state.whitelist
-        .iter()
-        .find(|user| &user.address == info.sender)
-        .map_or(0, |user| user.amount);
+    // Attempt to load user, or initialize a new one if not found
+    let usr = USER_INFO.may_load(deps.storage, sender.clone())?.unwrap_or(User {
+        buy_phases: HashMap::new(),
+        amount: 0,
+        tokens: Uint128::zero(),
+        claimed_amount: Uint128::zero(),
+        paid: Uint128::zero(),
+        refunded: false,
+        committed: HashMap::new(),
+        bids: HashMap::new(),
+    });
 
-    // Check individual buy limit
-    if (amount > current_phase.limit || amount <= 0) && wl_user < amount {
-        return Err(
-            StdError::generic_err(
-                format!(
-                    "Invalid amount, tried to buy {} over {} limit",
-                    amount,
-                    current_phase.limit
-                )
-            )
-        );
+    // Determine the current phase, return an error if not found or not in phase time
+    let mut current_phase = current_phase(current_time, state.phases.clone()).ok_or(
+        ContractError::NoActivePhase {}
+    )?;
+    if let Some(reason) = current_phase.pause_reason.clone() {
+        return Err(ContractError::PhasePaused { phase: current_phase.name, reason });
     }
 
-    // Check if user has already reached the limit for this phase
-    if
-        *usr.buy_phases.get(&current_phase.name).unwrap_or(&0) + amount > current_phase.limit &&
-        wl_user < amount
-    {
-        return Err(
-            StdError::generic_err(format!("Max buy phase ({}) reached", current_phase.name))
-        );
+    // Proven allocations extend the allowlists stored on chain
+    let phase_allocation = phase_proof
+        .map(|proof| verify_allocation(&current_phase.merkle_root, sender, proof))
+        .transpose()?;
+    let whitelist_allocation = whitelist_proof
+        .map(|proof| verify_allocation(&state.whitelist_merkle_root, sender, proof))
+        .transpose()?;
+
+    // Signature gated phases only accept purchases approved by the backend signer
+    let authorized_amount = match &authorization {
+        Some(authorization) => {
+            verify_authorization(deps, env, state, sender, &current_phase, authorization)?;
+            Some(authorization.max_amount)
+        }
+        None if current_phase.signature_required => {
+            return Err(ContractError::AuthorizationRequired { phase: current_phase.name });
+        }
+        None => None,
+    };
+
+    let eligible =
+        phase_allocation.is_some() ||
+        authorized_amount.is_some() ||
+        is_eligible(deps.storage, &current_phase, sender)?;
+    if let Some(limit) = phase_allocation.into_iter().chain(authorized_amount).min() {
+        current_phase.limit = limit;
     }
+    let wl_user = WHITELIST.may_load(deps.storage, sender.clone())?
+        .unwrap_or(0)
+        .max(whitelist_allocation.unwrap_or(0));
+
+    // Price of the whole deposit at the current block and phase sales
+    let cost = current_phase.cost(amount, current_time);
+
+    Ok(DepositPlan {
+        phase: current_phase,
+        cost,
+        eligible,
+        wl_user,
+        usr,
+        authorized_amount,
+        nonce: authorization.map(|authorization| authorization.nonce),
+    })
+}
 
-    // Check payment
-    if
-        info.funds.is_empty() ||
-        info.funds[0].denom != state.buy_denom ||
-        info.funds[0].amount != current_phase.price_per_token * Uint128::from(amount)
-    {
-        return Err(
-            StdError::generic_err(
-                format!(
-                    "Payment Failed, expected {}, got {}",
-                    current_phase.price_per_token * Uint128::from(amount),
-                    info.funds[0].amount
-                )
-            )
-        );
+impl DepositPlan {
+    // Units the phase can still take, pro-rata phases can be oversubscribed
+    fn remaining_supply(&self) -> u64 {
+        match self.phase.mode {
+            PhaseMode::ProRata => self.phase.supply.saturating_sub(self.phase.total_committed),
+            _ => self.phase.supply.saturating_sub(self.phase.total_minted),
+        }
     }
 
-    Ok(())
+    fn bought(&self) -> u64 {
+        self.usr.buy_phases.get(&self.phase.name).unwrap_or(&0) +
+            self.usr.committed.get(&self.phase.name).unwrap_or(&0)
+    }
 }
 
-fn get_previous_phase(current_phase: &Phase, all_phases: &Vec<Phase>) -> Option<Phase> {
-    // Find the index of the current phase
+fn validate_deposit(plan: &DepositPlan, amount: u64) -> Result<(), ContractError> {
+    let (current_phase, wl_user) = (&plan.phase, plan.wl_user);
+    if !plan.eligible {
+        return Err(ContractError::NotInPhase { phase: current_phase.name.clone() });
+    }
+
+    // Check phase supply limits, pro-rata phases can be oversubscribed
     if
-        let Some(current_index) = all_phases
-            .clone()
-            .iter()
-            .position(|phase| phase.name == current_phase.name)
+        current_phase.mode != PhaseMode::ProRata &&
+        current_phase.total_minted + amount > current_phase.supply
     {
-        // Check if there is a previous phase
-        if current_index > 0 {
-            // Return the previous phase
-            return all_phases.get(current_index - 1).cloned();
+        return Err(ContractError::Overbuy {});
+    }
+
+    // Check individual buy limit
+    if (amount > current_phase.limit || amount == 0) && wl_user < amount {
+        return Err(ContractError::ExceedsLimit { amount, limit: current_phase.limit });
+    }
+
+    // Check if user has already reached the limit for this phase
+    if plan.bought() + amount > current_phase.limit && wl_user < amount {
+        return Err(ContractError::PhaseLimitReached { phase: current_phase.name.clone() });
+    }
+
+    // The signed amount holds whatever the whitelist allows
+    if let Some(max_amount) = plan.authorized_amount {
+        if plan.bought() + amount > max_amount {
+            return Err(ContractError::ExceedsLimit { amount, limit: max_amount });
         }
     }
-    // If no previous phase exists or current phase not found, return None
-    None
+
+    Ok(())
+}
+
+fn validate_payment(
+    payment: &[Coin],
+    buy_denom: &str,
+    expected: Uint128
+) -> Result<(), ContractError> {
+    let paid = payment
+        .iter()
+        .find(|coin| coin.denom == buy_denom)
+        .map_or(Uint128::zero(), |coin| coin.amount);
+    if payment.len() != 1 || paid != expected {
+        return Err(ContractError::WrongPayment { expected, got: paid });
+    }
+
+    Ok(())
+}
+
+// Returns the proven allocation, a proof is only valid against a configured root
+fn verify_allocation(
+    root: &Option<String>,
+    address: &Addr,
+    proof: AllocationProof
+) -> Result<u64, ContractError> {
+    match root {
+        Some(root) if verify_proof(root, address, proof.allocation, &proof.proof) =>
+            Ok(proof.allocation),
+        _ => Err(ContractError::InvalidMerkleProof {}),
+    }
+}
+
+fn verify_authorization(
+    deps: Deps,
+    env: &Env,
+    state: &State,
+    buyer: &Addr,
+    phase: &Phase,
+    authorization: &PurchaseAuthorization
+) -> Result<(), ContractError> {
+    if env.block.time.seconds() > authorization.expiry {
+        return Err(ContractError::AuthorizationExpired {});
+    }
+    if USED_NONCES.has(deps.storage, authorization.nonce) {
+        return Err(ContractError::NonceUsed { nonce: authorization.nonce });
+    }
+    let signer_pubkey = state.signer_pubkey.as_ref().ok_or(ContractError::InvalidSignature {})?;
+
+    let payload = AuthorizationPayload {
+        contract: env.contract.address.to_string(),
+        buyer: buyer.to_string(),
+        phase: phase.name.clone(),
+        max_amount: authorization.max_amount,
+        expiry: authorization.expiry,
+        nonce: authorization.nonce,
+    };
+    let hash = Sha256::digest(to_json_vec(&payload)?);
+    let valid = deps.api
+        .secp256k1_verify(&hash, &authorization.signature, signer_pubkey)
+        .unwrap_or(false);
+    if !valid {
+        return Err(ContractError::InvalidSignature {});
+    }
+    Ok(())
 }
 
 fn record_deposit(
     state: &State,
     amount: u64,
+    cost: Uint128,
     usr: &mut User,
     current_phase: &Phase
-) -> StdResult<()> {
-    usr.amount += amount;
-    *usr.buy_phases.entry(current_phase.name.clone()).or_insert(0) += amount;
-    usr.tokens += Uint128::from(amount) * state.tokens_per_buy;
+) -> Result<(), ContractError> {
+    if current_phase.mode == PhaseMode::ProRata {
+        *usr.committed.entry(current_phase.name.clone()).or_insert(0) += amount;
+    } else {
+        usr.amount += amount;
+        *usr.buy_phases.entry(current_phase.name.clone()).or_insert(0) += amount;
+        usr.tokens += Uint128::from(amount) * state.tokens_per_buy;
+    }
+    if let PhaseMode::DutchAuction { .. } = current_phase.mode {
+        *usr.bids.entry(current_phase.name.clone()).or_default() += cost;
+    }
+    usr.paid += cost;
     // Assuming current_phase is a mutable reference if you want to update total_minted here, you'd need to adjust the function signature or manage this outside.
     Ok(())
 }
 
-fn add_phase(deps: DepsMut, env: Env, info: MessageInfo, phase: Phase) -> StdResult<Response> {
+fn add_phase(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    phase: Phase
+) -> Result<Response, ContractError> {
     let mut state = STATE.load(deps.storage)?;
 
-    // Authorization check
-    if state.admin != info.sender {
-        return Err(StdError::generic_err("Unauthorized: not admin"));
-    }
+    ensure_role(deps.as_ref(), &state, &info.sender, Role::PhaseOperator)?;
 
-    // Launchpad start check
-    if state.is_launchpad_started(env) {
-        return Err(StdError::generic_err("Unauthorized: Launchpad started"));
-    }
+    ensure_phases_editable(&state, &env)?;
 
     // Validate the new phase
-    validate_phase(&phase)?;
+    validate_new_phase(&phase)?;
 
     // Check for existing phase with the same name
     if state.phases.iter().any(|p| p.name == phase.name) {
-        return Err(StdError::generic_err("Phase already exists"));
+        return Err(ContractError::PhaseExists { name: phase.name });
     }
 
     // Add the new phase
     state.phases.push(phase.clone());
+    validate_supply(&state)?;
     STATE.save(deps.storage, &state)?;
 
     Ok(Response::new().add_attribute("action", "add_phase").add_attribute("phase_name", phase.name))
 }
 
+#[allow(clippy::too_many_arguments)]
 fn update_phase(
     deps: DepsMut,
     env: Env,
@@ -497,25 +1048,19 @@ fn update_phase(
     end_time: Option<u64>,
     price_per_token: Option<Uint128>,
     supply: Option<u64>,
-    address_list: Option<Vec<Addr>>,
-    limit: Option<u64>
-) -> StdResult<Response> {
+    limit: Option<u64>,
+    merkle_root: Option<String>
+) -> Result<Response, ContractError> {
     let mut state = STATE.load(deps.storage)?;
-    // Authorization check
-    if state.admin != info.sender {
-        return Err(StdError::generic_err("Unauthorized: not admin"));
-    }
+    ensure_role(deps.as_ref(), &state, &info.sender, Role::PhaseOperator)?;
 
-    // Launchpad start check
-    if state.is_launchpad_started(env) {
-        return Err(StdError::generic_err("Unauthorized: Launchpad started"));
-    }
+    ensure_phases_editable(&state, &env)?;
 
     // Find and update the phase
     let phase = state.phases
         .iter_mut()
         .find(|p| p.name == name)
-        .ok_or_else(|| StdError::generic_err("Phase does not exist"))?;
+        .ok_or_else(|| ContractError::PhaseNotFound { name: name.clone() })?;
 
     // Apply updates
     if let Some(start) = start_time {
@@ -525,106 +1070,679 @@ fn update_phase(
         phase.end_time = end;
     }
     if let Some(price) = price_per_token {
+        // Pro-rata commitments were paid at the listed price
+        if phase.total_committed > 0 && price != phase.price_per_token {
+            return Err(ContractError::PriceLocked { phase: name });
+        }
         phase.price_per_token = price;
     }
     if let Some(supply) = supply {
+        if supply < phase.sold() {
+            return Err(ContractError::SupplyBelowSold { sold: phase.sold() });
+        }
         phase.supply = supply;
     }
-    if let Some(list) = address_list {
-        phase.address_list = list;
-    }
     if let Some(limit) = limit {
         phase.limit = limit;
     }
+    if merkle_root.is_some() {
+        phase.merkle_root = merkle_root;
+    }
 
     // Validate the updated phase
     validate_phase(phase)?;
+    validate_supply(&state)?;
 
     STATE.save(deps.storage, &state)?;
 
     Ok(Response::new().add_attribute("action", "update_phase").add_attribute("phase_name", name))
 }
 
-fn remove_phase(deps: DepsMut, env: Env, info: MessageInfo, name: String) -> StdResult<Response> {
+fn remove_phase(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    name: String
+) -> Result<Response, ContractError> {
     let mut state = STATE.load(deps.storage)?;
 
-    // Authorization check
-    if state.admin != info.sender {
-        return Err(StdError::generic_err("Unauthorized: not admin"));
-    }
+    ensure_role(deps.as_ref(), &state, &info.sender, Role::PhaseOperator)?;
 
-    // Launchpad start check
-    if state.is_launchpad_started(env) {
-        return Err(StdError::generic_err("Unauthorized: Launchpad started"));
-    }
+    ensure_editable(&state, &env)?;
 
     // Remove the phase
     let initial_len = state.phases.len();
     state.phases.retain(|p| p.name != name);
     if state.phases.len() == initial_len {
-        return Err(StdError::generic_err("Phase doesn't exist"));
+        return Err(ContractError::PhaseNotFound { name });
     }
 
+    // A phase re-added under the same name starts from an empty allowlist generation
+    PHASE_ALLOWLIST_GENERATION.update(deps.storage, name.clone(), |generation| -> StdResult<_> {
+        Ok(generation.unwrap_or(0) + 1)
+    })?;
+    PHASE_ALLOWLIST_SIZE.remove(deps.storage, name.clone());
+
     STATE.save(deps.storage, &state)?;
 
     Ok(Response::new().add_attribute("action", "remove_phase").add_attribute("phase_name", name))
 }
 
-// Utility function for checking if the launchpad has started
-impl State {
-    fn is_launchpad_started(&self, env: Env) -> bool {
-        let current_time = env.block.time.seconds(); // Adjust based on actual env usage
-        current_time > self.start_time
+// Phases, whitelist and allowlists can only be edited before the sale starts
+fn ensure_editable(state: &State, env: &Env) -> Result<(), ContractError> {
+    match state.status(env) {
+        SaleStatus::Draft | SaleStatus::Upcoming => Ok(()),
+        SaleStatus::Cancelled => Err(invalid_status(SaleStatus::Cancelled)),
+        _ => Err(ContractError::SaleStarted {}),
     }
 }
 
-// Phase validation before adding/updating
-fn validate_phase(phase: &Phase) -> StdResult<()> {
-    if phase.start_time > phase.end_time {
-        Err(StdError::generic_err("Start time must be before end time"))
-    } else if phase.supply == 0 {
-        Err(StdError::generic_err("Max deposit must be > 0"))
-    } else if phase.price_per_token.is_zero() {
-        Err(StdError::generic_err("Price must be > 0"))
-    } else {
-        Ok(())
+// A paused sale can also fix its phase prices and schedule before resuming
+fn ensure_phases_editable(state: &State, env: &Env) -> Result<(), ContractError> {
+    match state.status(env) {
+        SaleStatus::Paused => Ok(()),
+        _ => ensure_editable(state, env),
     }
 }
 
-fn withdraw(
-    deps: DepsMut,
-    env: Env,
-    info: MessageInfo,
-    denom: String,
-    token_type: String
-) -> StdResult<Response> {
-    let state = STATE.load(deps.storage)?;
-    if state.admin != info.sender {
-        return Err(StdError::generic_err("Unauthorized: not admin"));
+// The admin and owners hold every role
+fn ensure_role(deps: Deps, state: &State, sender: &Addr, role: Role) -> Result<(), ContractError> {
+    if *sender == state.admin {
+        return Ok(());
     }
 
-    let amount_raised = get_token_amount(
-        deps.querier,
-        denom.clone(),
-        env.contract.address,
-        token_type.clone()
-    )?;
-
-    let msg = transfer_token_message(denom.clone(), token_type, amount_raised, info.sender)?;
+    let roles = ROLES.may_load(deps.storage, sender.clone())?.unwrap_or_default();
+    if roles.contains(&Role::Owner) || roles.contains(&role) {
+        return Ok(());
+    }
+    Err(ContractError::MissingRole { role: role.to_string() })
+}
 
-    Ok(Response::new().add_message(msg))
+fn invalid_status(status: SaleStatus) -> ContractError {
+    ContractError::InvalidStatus { status: status.to_string() }
 }
 
-fn flip_claim_status(deps: DepsMut, _env: Env, info: MessageInfo) -> StdResult<Response> {
+impl State {
+    // Lifecycle status, steps taken by the admin come first and the clock drives the rest
+    fn status(&self, env: &Env) -> SaleStatus {
+        let current_time = env.block.time.seconds();
+        match self.stage {
+            SaleStage::Cancelled => SaleStatus::Cancelled,
+            SaleStage::Finalized if self.claimable => SaleStatus::Claimable,
+            SaleStage::Finalized => SaleStatus::Finalized,
+            SaleStage::Open if current_time > self.end_time => {
+                if self.is_soft_cap_reached() {
+                    SaleStatus::Ended
+                } else {
+                    SaleStatus::Refunding
+                }
+            }
+            SaleStage::Open if self.phases.is_empty() => SaleStatus::Draft,
+            SaleStage::Open if current_time < self.start_time => SaleStatus::Upcoming,
+            SaleStage::Open if self.pause_reason.is_some() => SaleStatus::Paused,
+            SaleStage::Open =>
+                SaleStatus::Active {
+                    phase: current_phase(current_time, self.phases.clone()).map(|phase| phase.name),
+                },
+        }
+    }
+
+    // Sum of the funds raised across all phases, in buy_denom
+    fn total_raised(&self) -> Uint128 {
+        self.phases.iter().map(|phase| phase.raised()).sum()
+    }
+
+    // Raised funds already credited to buyers, unsettled pro-rata commitments excluded
+    fn total_settled(&self) -> Uint128 {
+        self.phases.iter().map(|phase| phase.total_raised).sum()
+    }
+
+    // Sale tokens sold across all phases, in whole tokens
+    fn total_allocated(&self) -> Uint128 {
+        self.phases
+            .iter()
+            .map(|phase| Uint128::from(phase.sold()) * self.tokens_per_buy)
+            .sum()
+    }
+
+    // Applies the rollover policy of every phase ended by `time`, in phase order so carried
+    // supply keeps rolling through consecutive ended phases. Burned or returned supply moves
+    // tokens, it waits for a `releasable` escrow amount held by the contract
+    fn apply_rollovers(&mut self, time: u64, mut releasable: Option<Uint128>) -> Vec<Rollover> {
+        let mut rollovers = vec![];
+        for index in 0..self.phases.len() {
+            let phase = &mut self.phases[index];
+            let deferred = phase.rollover.moves_tokens() && releasable.is_none();
+            if phase.rolled_over || time <= phase.end_time || deferred {
+                continue;
+            }
+            phase.rolled_over = true;
+
+            let unsold = phase.supply - phase.sold();
+            let mut rollover = Rollover {
+                phase: phase.name.clone(),
+                policy: phase.rollover.clone(),
+                unsold,
+                next_phase: None,
+                released: Uint128::zero(),
+            };
+            let mut allowlists = phase.inherited_allowlists.clone();
+            allowlists.push(phase.name.clone());
+
+            match phase.rollover {
+                RolloverPolicy::None => {}
+                RolloverPolicy::CarrySupply | RolloverPolicy::CarrySupplyAndAllowlist => {
+                    let carry_allowlist = phase.rollover == RolloverPolicy::CarrySupplyAndAllowlist;
+                    // The last phase has nowhere to carry its supply, it stays unsold
+                    if let Some(next) = self.phases.get_mut(index + 1) {
+                        next.supply += unsold;
+                        if carry_allowlist {
+                            next.inherited_allowlists.extend(allowlists);
+                        }
+                        rollover.next_phase = Some(next.name.clone());
+                        self.phases[index].supply -= unsold;
+                    }
+                }
+                RolloverPolicy::Burn | RolloverPolicy::ReturnToProject => {
+                    phase.supply -= unsold;
+                    let tokens = Uint128::from(unsold) * self.tokens_per_buy;
+                    let tokens = u64::try_from(tokens.u128()).unwrap_or(u64::MAX);
+                    self.token_info.supply = self.token_info.supply.saturating_sub(tokens);
+
+                    // Only the escrow above what the sale still needs is released, and never
+                    // more than the contract holds beyond the buyers' tokens
+                    if let (Some(required), Some(decimals), Some(available)) = (
+                        self.escrow_required(),
+                        self.token_info.decimals,
+                        releasable.as_mut(),
+                    ) {
+                        let amount =
+                            Uint128::from(tokens) * Uint128::from(u64::pow(10, decimals as u32));
+                        rollover.released = self.escrowed
+                            .saturating_sub(required)
+                            .min(amount)
+                            .min(*available);
+                        self.escrowed -= rollover.released;
+                        *available -= rollover.released;
+                    }
+                }
+            }
+            rollovers.push(rollover);
+        }
+        rollovers
+    }
+
+    // Sale tokens offered across all phases, in whole tokens
+    fn phases_supply(&self) -> Uint128 {
+        self.phases
+            .iter()
+            .map(|phase| Uint128::from(phase.supply) * self.tokens_per_buy)
+            .sum()
+    }
+
+    // Sale tokens to escrow, the phases supply or the token sale supply if larger, in base units
+    fn escrow_required(&self) -> Option<Uint128> {
+        let decimals = self.token_info.decimals?;
+        if self.token_info.kind.is_minted() {
+            return Some(Uint128::zero());
+        }
+
+        let sale_supply = self.phases_supply().max(Uint128::from(self.token_info.supply));
+        Some(sale_supply * Uint128::from(u64::pow(10, decimals as u32)))
+    }
+
+    fn is_soft_cap_reached(&self) -> bool {
+        match self.soft_cap {
+            Some(soft_cap) => self.total_raised() >= soft_cap,
+            None => true,
+        }
+    }
+}
+
+impl SaleTokenKind {
+    // Token type understood by the transfer and balance helpers
+    pub(crate) fn token_type(&self) -> String {
+        match self {
+            SaleTokenKind::Native | SaleTokenKind::TokenFactory => "native".to_string(),
+            _ => "cw20".to_string(),
+        }
+    }
+
+    // Issued by the launchpad on claim rather than funded upfront
+    pub(crate) fn is_minted(&self) -> bool {
+        matches!(self, SaleTokenKind::TokenFactory | SaleTokenKind::Cw20Minter { .. })
+    }
+}
+
+impl RolloverPolicy {
+    // Unsold supply leaves the sale, along with its escrowed tokens
+    fn moves_tokens(&self) -> bool {
+        matches!(self, RolloverPolicy::Burn | RolloverPolicy::ReturnToProject)
+    }
+}
+
+impl SaleStatus {
+    // The sale did not reach its end time and was not cancelled
+    fn is_running(&self) -> bool {
+        matches!(
+            self,
+            SaleStatus::Draft |
+                SaleStatus::Upcoming |
+                SaleStatus::Active { .. } |
+                SaleStatus::Paused
+        )
+    }
+}
+
+impl Phase {
+    // Amount sold, pro-rata phases sell up to their supply once oversubscribed
+    fn sold(&self) -> u64 {
+        match self.mode {
+            PhaseMode::ProRata => self.total_committed.min(self.supply).max(self.total_minted),
+            _ => self.total_minted,
+        }
+    }
+
+    fn raised(&self) -> Uint128 {
+        match self.mode {
+            PhaseMode::ProRata => self.price_per_token * Uint128::from(self.sold()),
+            PhaseMode::DutchAuction { .. } => self.settlement_price() * Uint128::from(self.sold()),
+            _ => self.total_raised,
+        }
+    }
+
+    // Price of the next `amount` units, tiers and curves are priced piecewise from total_minted
+    fn cost(&self, amount: u64, time: u64) -> Uint128 {
+        match &self.mode {
+            PhaseMode::Tiered { tiers } => {
+                let mut cost = Uint128::zero();
+                let mut position = self.total_minted;
+                let mut remaining = amount;
+                let mut tier_end = 0u64;
+                for (index, tier) in tiers.iter().enumerate() {
+                    tier_end += tier.supply;
+                    let units = if index + 1 == tiers.len() {
+                        remaining
+                    } else {
+                        tier_end.saturating_sub(position).min(remaining)
+                    };
+                    cost += tier.price * Uint128::from(units);
+                    position += units;
+                    remaining -= units;
+                }
+                cost
+            }
+            PhaseMode::BondingCurve { slope } => {
+                // Sum of the unit indexes from total_minted to total_minted + amount - 1
+                let (amount, minted) = (amount as u128, self.total_minted as u128);
+                let steps = (amount * (2 * minted + amount).saturating_sub(1)) / 2;
+                self.price_per_token * Uint128::from(amount) + *slope * Uint128::from(steps)
+            }
+            _ => self.price_at(time) * Uint128::from(amount),
+        }
+    }
+
+    // Dutch auction prices decay from price_per_token to the floor over the phase window
+    fn price_at(&self, time: u64) -> Uint128 {
+        let (floor_price, step) = match self.mode {
+            PhaseMode::DutchAuction { floor_price, step } => (floor_price, step),
+            _ => {
+                return self.price_per_token;
+            }
+        };
+        let duration = self.end_time - self.start_time;
+        if duration == 0 {
+            return self.price_per_token;
+        }
+
+        let mut elapsed = time.clamp(self.start_time, self.end_time) - self.start_time;
+        if let Some(step) = step {
+            elapsed -= elapsed % step;
+        }
+        let decay = (self.price_per_token - floor_price).multiply_ratio(elapsed, duration);
+        self.price_per_token - decay
+    }
+
+    // Auction buyers all pay the price the supply sold out at, or the floor price
+    fn settlement_price(&self) -> Uint128 {
+        match self.mode {
+            PhaseMode::DutchAuction { floor_price, .. } =>
+                self.clearing_price.unwrap_or(floor_price),
+            _ => self.price_per_token,
+        }
+    }
+
+    // Share of the supply allocated to a commitment, rounded down
+    fn pro_rata_allocation(&self, committed: u64) -> u64 {
+        if self.total_committed <= self.supply {
+            committed
+        } else {
+            Uint128::from(committed).multiply_ratio(self.supply, self.total_committed).u128() as u64
+        }
+    }
+}
+
+fn validate_vesting(vesting: &VestingConfig) -> Result<(), ContractError> {
+    if vesting.tge_unlock_percentage > BASIS_POINTS {
+        Err(ContractError::InvalidTgeUnlock {})
+    } else if vesting.period.is_some_and(|period| period == 0 || period > vesting.duration) {
+        Err(ContractError::InvalidVestingPeriod {})
+    } else {
+        Ok(())
+    }
+}
+
+// Phase validation before adding/updating
+// Sales counters are kept by the contract, a new phase starts from zero
+fn validate_new_phase(phase: &Phase) -> Result<(), ContractError> {
+    let counters_set =
+        phase.total_minted > 0 ||
+        !phase.total_raised.is_zero() ||
+        phase.total_committed > 0 ||
+        phase.clearing_price.is_some() ||
+        phase.rolled_over ||
+        !phase.inherited_allowlists.is_empty();
+    if counters_set {
+        return Err(ContractError::InvalidPhaseCounters {});
+    }
+    validate_phase(phase)
+}
+
+fn validate_phase(phase: &Phase) -> Result<(), ContractError> {
+    if phase.start_time > phase.end_time {
+        Err(ContractError::InvalidTimeRange {})
+    } else if phase.supply == 0 {
+        Err(ContractError::InvalidPhaseSupply {})
+    } else if phase.price_per_token.is_zero() {
+        Err(ContractError::InvalidPhasePrice {})
+    } else {
+        if let Some(root) = &phase.merkle_root {
+            validate_merkle_root(root)?;
+        }
+        match &phase.mode {
+            PhaseMode::DutchAuction { floor_price, step } if
+                floor_price.is_zero() ||
+                *floor_price > phase.price_per_token ||
+                *step == Some(0)
+            => Err(ContractError::InvalidAuction {}),
+            PhaseMode::Tiered { tiers } if
+                tiers.is_empty() ||
+                tiers.iter().any(|tier| tier.supply == 0 || tier.price.is_zero())
+            => Err(ContractError::InvalidTiers {}),
+            _ => Ok(()),
+        }
+    }
+}
+
+// Unsold supply handed over by an ended phase
+struct Rollover {
+    phase: String,
+    policy: RolloverPolicy,
+    unsold: u64,
+    // Phase receiving the carried supply
+    next_phase: Option<String>,
+    // Escrowed sale tokens burned or sent to the withdraw wallet, in base units
+    released: Uint128,
+}
+
+// Records each rollover in an event, moving the released escrow out of the contract
+fn rollover_response(state: &State, rollovers: Vec<Rollover>) -> StdResult<Response> {
+    let mut response = Response::new();
+    for rollover in rollovers {
+        let mut event = Event::new("rollover")
+            .add_attribute("phase", rollover.phase)
+            .add_attribute("policy", rollover.policy.to_string())
+            .add_attribute("unsold", rollover.unsold.to_string());
+        if let Some(next_phase) = rollover.next_phase {
+            event = event.add_attribute("next_phase", next_phase);
+        }
+
+        let amount = rollover.released;
+        if let Some(denom) = state.token_info.denom.clone().filter(|_| !amount.is_zero()) {
+            let token_type = state.token_info.kind.token_type();
+            let msg = match rollover.policy {
+                RolloverPolicy::Burn if token_type == "native" =>
+                    (BankMsg::Burn { amount: vec![Coin { denom, amount }] }).into(),
+                RolloverPolicy::Burn =>
+                    CosmosMsg::Wasm(WasmMsg::Execute {
+                        contract_addr: denom,
+                        funds: vec![],
+                        msg: to_json_binary(&(Cw20ExecuteMsg::Burn { amount }))?,
+                    }),
+                _ => {
+                    let receiver = state.withdraw_wallet.clone();
+                    transfer_token_message(denom, token_type, amount, receiver)?
+                }
+            };
+            response = response.add_message(msg);
+            event = event.add_attribute("released", amount.to_string());
+        }
+        response = response.add_event(event);
+    }
+    Ok(response)
+}
+
+fn rollover_phases(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
     let mut state = STATE.load(deps.storage)?;
-    if state.admin != info.sender {
-        return Err(StdError::generic_err("Unauthorized: not admin"));
+    let releasable = releasable_escrow(deps.as_ref(), &env, &state)?;
+    let rollovers = state.apply_rollovers(env.block.time.seconds(), Some(releasable));
+    if rollovers.is_empty() {
+        return Err(ContractError::NothingToRollOver {});
+    }
+    STATE.save(deps.storage, &state)?;
+
+    Ok(rollover_response(&state, rollovers)?.add_attribute("action", "rollover_phases"))
+}
+
+// Escrowed sale tokens held by the contract beyond the tokens owed to buyers, in base units
+fn releasable_escrow(deps: Deps, env: &Env, state: &State) -> StdResult<Uint128> {
+    // Only ended phases burning or returning their supply release escrow
+    let time = env.block.time.seconds();
+    let pending = state.phases
+        .iter()
+        .any(|phase| !phase.rolled_over && time > phase.end_time && phase.rollover.moves_tokens());
+    if !pending {
+        return Ok(Uint128::zero());
+    }
+
+    let (denom, decimals) = match (&state.token_info.denom, state.token_info.decimals) {
+        (Some(denom), Some(decimals)) if !state.token_info.kind.is_minted() => {
+            (denom.clone(), decimals)
+        }
+        _ => {
+            return Ok(Uint128::zero());
+        }
+    };
+
+    let balance = get_token_amount(
+        deps.querier,
+        denom,
+        env.contract.address.clone(),
+        state.token_info.kind.token_type()
+    )?;
+    Ok(balance.saturating_sub(reserved_tokens(state, &state.status(env), decimals)))
+}
+
+// Sale tokens owed to buyers, a failed or cancelled sale owes nothing
+fn reserved_tokens(state: &State, status: &SaleStatus, decimals: u8) -> Uint128 {
+    match status {
+        SaleStatus::Refunding | SaleStatus::Cancelled => Uint128::zero(),
+        _ =>
+            state.total_allocated() * Uint128::from(u64::pow(10, decimals as u32)) -
+            state.total_claimed,
+    }
+}
+
+// State at the block time, queries see the carried supply of ended phases before it is stored
+fn load_state(storage: &dyn Storage, env: &Env) -> StdResult<State> {
+    let mut state = STATE.load(storage)?;
+    state.apply_rollovers(env.block.time.seconds(), None);
+    Ok(state)
+}
+
+// Phases can't sell more than the token sale supply
+fn validate_supply(state: &State) -> Result<(), ContractError> {
+    let allocated = state.phases_supply();
+    if allocated > Uint128::from(state.token_info.supply) {
+        return Err(ContractError::SupplyExceeded { allocated, supply: state.token_info.supply });
+    }
+    Ok(())
+}
+
+fn validate_merkle_root(root: &str) -> Result<(), ContractError> {
+    match parse_hash(root) {
+        Some(_) => Ok(()),
+        None => Err(ContractError::InvalidMerkleRoot {}),
+    }
+}
+
+fn withdraw(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let mut state = STATE.load(deps.storage)?;
+    ensure_role(deps.as_ref(), &state, &info.sender, Role::Treasurer)?;
+
+    // Raised funds are released by finalizing the sale, they stay locked for refunds otherwise
+    match state.status(&env) {
+        SaleStatus::Finalized | SaleStatus::Claimable => {}
+        SaleStatus::Refunding => {
+            return Err(ContractError::SoftCapNotReached {});
+        }
+        status => {
+            return Err(invalid_status(status));
+        }
+    }
+
+    // Only the funds raised by the sale and not withdrawn yet can leave the contract
+    let amount_raised = state.total_settled() - state.withdrawn;
+    if amount_raised.is_zero() {
+        return Err(ContractError::NothingToWithdraw {});
+    }
+
+    let fees = amount_raised.multiply_ratio(state.fees_percentage, BASIS_POINTS);
+    let project_amount = amount_raised - fees;
+
+    let mut msgs = vec![
+        transfer_token_message(
+            state.buy_denom.clone(),
+            state.buy_token_type.clone(),
+            project_amount,
+            state.withdraw_wallet.clone()
+        )?
+    ];
+    if !fees.is_zero() {
+        msgs.push(
+            transfer_token_message(
+                state.buy_denom.clone(),
+                state.buy_token_type.clone(),
+                fees,
+                state.fees_wallet.clone()
+            )?
+        );
+    }
+
+    state.fees_collected += fees;
+    state.withdrawn += amount_raised;
+    STATE.save(deps.storage, &state)?;
+
+    Ok(
+        Response::new()
+            .add_messages(msgs)
+            .add_attribute("action", "withdraw")
+            .add_attribute("amount", project_amount.to_string())
+            .add_attribute("fees", fees.to_string())
+    )
+}
+
+fn withdraw_unsold(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let mut state = STATE.load(deps.storage)?;
+    ensure_role(deps.as_ref(), &state, &info.sender, Role::Treasurer)?;
+
+    // Allocations are only final once the sale is over
+    let status = state.status(&env);
+    if status.is_running() {
+        return Err(ContractError::SaleNotEnded {});
+    }
+
+    let (denom, decimals) = match (state.token_info.denom.clone(), state.token_info.decimals) {
+        (Some(denom), Some(decimals)) => (denom, decimals),
+        _ => {
+            return Err(ContractError::TokenNotSettled {});
+        }
+    };
+
+    // Minted tokens are issued on claim, the contract holds none
+    if state.token_info.kind.is_minted() {
+        return Err(ContractError::NothingToWithdraw {});
+    }
+
+    // Tokens owed to buyers stay reserved
+    let reserved = reserved_tokens(&state, &status, decimals);
+
+    let balance = get_token_amount(
+        deps.querier,
+        denom.clone(),
+        env.contract.address.clone(),
+        state.token_info.kind.token_type()
+    )?;
+    let surplus = balance.saturating_sub(reserved);
+    if surplus.is_zero() {
+        return Err(ContractError::NothingToWithdraw {});
+    }
+
+    // Withdrawn tokens no longer count towards the escrow, the sale keeps only what it sold
+    state.escrowed = state.escrowed.saturating_sub(surplus);
+    for phase in state.phases.iter_mut() {
+        phase.supply = phase.sold();
+        phase.rolled_over = true;
+    }
+    let allocated = state.total_allocated();
+    state.token_info.supply = u64::try_from(allocated.u128()).unwrap_or(u64::MAX);
+    STATE.save(deps.storage, &state)?;
+
+    let msg = sale_token_message(&env, &state.token_info, denom, surplus, state.withdraw_wallet)?;
+
+    Ok(
+        Response::new()
+            .add_message(msg)
+            .add_attribute("action", "withdraw_unsold")
+            .add_attribute("amount", surplus.to_string())
+    )
+}
+
+fn flip_claim_status(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo
+) -> Result<Response, ContractError> {
+    let mut state = STATE.load(deps.storage)?;
+    ensure_role(deps.as_ref(), &state, &info.sender, Role::Owner)?;
+
+    // Claims can only be toggled on a finalized sale
+    match state.status(&env) {
+        SaleStatus::Finalized | SaleStatus::Claimable => {}
+        SaleStatus::Refunding => {
+            return Err(ContractError::SoftCapNotReached {});
+        }
+        status => {
+            return Err(invalid_status(status));
+        }
     }
 
     state.claimable = !state.claimable;
 
     if state.claimable && (state.token_info.denom.is_none() || state.token_info.decimals.is_none()) {
-        return Err(StdError::generic_err("Token denom not settled"));
+        return Err(ContractError::TokenNotSettled {});
+    }
+
+    // Claims only open once the escrow covers the sale
+    if let Some(required) = state.escrow_required().filter(|_| state.claimable) {
+        if state.escrowed < required {
+            return Err(ContractError::Underfunded { escrowed: state.escrowed, required });
+        }
+    }
+
+    // The first time claims open marks the TGE the vesting schedule starts from
+    if state.claimable && state.tge_time.is_none() {
+        state.tge_time = Some(env.block.time.seconds());
     }
 
     STATE.save(deps.storage, &state)?;
@@ -636,35 +1754,464 @@ fn flip_claim_status(deps: DepsMut, _env: Env, info: MessageInfo) -> StdResult<R
     )
 }
 
-fn claim_token(deps: DepsMut, _env: Env, info: MessageInfo) -> StdResult<Response> {
+fn claim_token(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    if state.status(&env) != SaleStatus::Claimable {
+        return Err(ContractError::NotClaimable {});
+    }
+    if let Some(reason) = state.pause_reason.clone() {
+        return Err(ContractError::Paused { reason });
+    }
+
+    let mut usr = USER_INFO.load(deps.storage, info.sender.clone()).map_err(|_|
+        ContractError::UserNotFound {}
+    )?;
+
+    // Buyers of a paused phase keep their tokens locked
+    for phase in state.phases.iter() {
+        let bought = usr.buy_phases.get(&phase.name).is_some_and(|amount| *amount > 0);
+        if let Some(reason) = phase.pause_reason.clone().filter(|_| bought) {
+            return Err(ContractError::PhasePaused { phase: phase.name.clone(), reason });
+        }
+    }
+
+    // Pro-rata allocations are only known once settled
+    if !usr.committed.is_empty() {
+        return Err(ContractError::NotSettled {});
+    }
+
+    let decimals = state.token_info.decimals.ok_or(ContractError::TokenNotSettled {})?;
+    let denom = state.token_info.denom.clone().ok_or(ContractError::TokenNotSettled {})?;
+    let total = usr.tokens * Uint128::from(u64::pow(10, decimals as u32));
+    if usr.claimed_amount >= total {
+        return Err(ContractError::AlreadyClaimed {});
+    }
+
+    let vested = vested_amount(
+        total,
+        &state.vesting,
+        state.tge_time.unwrap_or_default(),
+        env.block.time.seconds()
+    );
+    let claim_amount = vested - usr.claimed_amount;
+    if claim_amount.is_zero() {
+        return Err(ContractError::NothingToClaim {});
+    }
+    STATE.update(deps.storage, |mut state| -> StdResult<_> {
+        state.total_claimed += claim_amount;
+        Ok(state)
+    })?;
+
+    let token_transfer_msg = sale_token_message(
+        &env,
+        &state.token_info,
+        denom,
+        claim_amount,
+        info.sender.clone()
+    )?;
+    usr.claimed_amount += claim_amount;
+    USER_INFO.save(deps.storage, info.sender, &usr)?;
+    Ok(
+        Response::new()
+            .add_message(token_transfer_msg)
+            .add_attribute("action", "claim_airdrop")
+            .add_attribute("amount", claim_amount.to_string())
+    )
+}
+
+fn claim_refund(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
     let state = STATE.load(deps.storage)?;
-    if !state.claimable {
-        return Err(StdError::generic_err("Not claimble"));
+    if !matches!(state.status(&env), SaleStatus::Cancelled | SaleStatus::Refunding) {
+        return Err(ContractError::RefundNotAvailable {});
     }
 
     let mut usr = USER_INFO.load(deps.storage, info.sender.clone()).map_err(|_|
-        StdError::generic_err("User not found")
+        ContractError::UserNotFound {}
     )?;
 
-    if usr.claimed == true {
-        return Err(StdError::generic_err("Already claimed"));
+    if usr.refunded || usr.paid.is_zero() {
+        return Err(ContractError::AlreadyRefunded {});
     }
 
-    let token_transfer_msg = transfer_token_message(
-        state.token_info.denom.clone().unwrap(),
-        "cw20".to_string(),
-        Uint128::from(u64::pow(10, state.token_info.decimals.unwrap() as u32)) * usr.tokens,
+    let refund_msg = transfer_token_message(
+        state.buy_denom.clone(),
+        state.buy_token_type.clone(),
+        usr.paid,
         info.sender.clone()
     )?;
-    usr.claimed = true;
+    usr.refunded = true;
     USER_INFO.save(deps.storage, info.sender, &usr)?;
-    Ok(Response::new().add_message(token_transfer_msg).add_attribute("action", "claim_airdrop"))
+
+    Ok(
+        Response::new()
+            .add_message(refund_msg)
+            .add_attribute("action", "claim_refund")
+            .add_attribute("amount", usr.paid.to_string())
+    )
+}
+
+fn settle(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    address: Option<String>
+) -> Result<Response, ContractError> {
+    let mut state = STATE.load(deps.storage)?;
+    // Failed and cancelled sales refund the whole payment instead
+    let status = state.status(&env);
+    if matches!(status, SaleStatus::Cancelled | SaleStatus::Refunding) {
+        return Err(invalid_status(status));
+    }
+
+    let address = match address {
+        Some(address) => deps.api.addr_validate(&address)?,
+        None => info.sender,
+    };
+    let mut usr = USER_INFO.load(deps.storage, address.clone()).map_err(|_|
+        ContractError::UserNotFound {}
+    )?;
+
+    // A refunded user already got back everything committed
+    if usr.refunded {
+        return Err(ContractError::AlreadyRefunded {});
+    }
+
+    let current_time = env.block.time.seconds();
+    let mut settled = false;
+    let mut allocated = 0u64;
+    let mut excess = Uint128::zero();
+    for phase in state.phases.iter_mut().filter(|phase| phase.end_time < current_time) {
+        match phase.mode {
+            PhaseMode::ProRata => {
+                let committed = match usr.committed.remove(&phase.name) {
+                    Some(committed) => committed,
+                    None => {
+                        continue;
+                    }
+                };
+
+                let allocation = phase.pro_rata_allocation(committed);
+                phase.total_minted += allocation;
+                phase.total_raised += phase.price_per_token * Uint128::from(allocation);
+
+                usr.amount += allocation;
+                *usr.buy_phases.entry(phase.name.clone()).or_insert(0) += allocation;
+                usr.tokens += Uint128::from(allocation) * state.tokens_per_buy;
+                allocated += allocation;
+                excess += phase.price_per_token * Uint128::from(committed - allocation);
+            }
+            // Auction buyers got their tokens on deposit, only the price is settled
+            PhaseMode::DutchAuction { .. } => {
+                let bid = match usr.bids.remove(&phase.name) {
+                    Some(bid) => bid,
+                    None => {
+                        continue;
+                    }
+                };
+
+                let bought = usr.buy_phases.get(&phase.name).copied().unwrap_or(0);
+                let cost = phase.settlement_price() * Uint128::from(bought);
+                phase.total_raised += cost;
+                allocated += bought;
+                excess += bid - cost;
+            }
+            _ => {
+                continue;
+            }
+        }
+        settled = true;
+    }
+    if !settled {
+        return Err(ContractError::NothingToSettle {});
+    }
+    usr.paid -= excess;
+
+    users().save(deps.storage, address.clone(), &(UserToken {
+        address: address.clone(),
+        tokens: usr.tokens,
+    }))?;
+    STATE.save(deps.storage, &state)?;
+    USER_INFO.save(deps.storage, address.clone(), &usr)?;
+
+    let mut response = Response::new()
+        .add_attribute("action", "settle")
+        .add_attribute("address", address.to_string())
+        .add_attribute("allocated", allocated.to_string())
+        .add_attribute("refund", excess.to_string());
+    if !excess.is_zero() {
+        response = response.add_message(
+            transfer_token_message(state.buy_denom, state.buy_token_type, excess, address)?
+        );
+    }
+    Ok(response)
+}
+
+fn finalize(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let mut state = STATE.load(deps.storage)?;
+    ensure_role(deps.as_ref(), &state, &info.sender, Role::Owner)?;
+
+    match state.status(&env) {
+        SaleStatus::Ended => {}
+        SaleStatus::Refunding => {
+            return Err(ContractError::SoftCapNotReached {});
+        }
+        status if status.is_running() => {
+            return Err(ContractError::SaleNotEnded {});
+        }
+        status => {
+            return Err(invalid_status(status));
+        }
+    }
+
+    let releasable = releasable_escrow(deps.as_ref(), &env, &state)?;
+    let rollovers = state.apply_rollovers(env.block.time.seconds(), Some(releasable));
+    let mut response = rollover_response(&state, rollovers)?.add_attribute("action", "finalize");
+    // Launchpad issued tokens are only created once the sale succeeded
+    match (state.token_info.kind.clone(), &state.token_info.denom) {
+        (SaleTokenKind::TokenFactory, _) => {
+            let decimals = state.token_info.decimals.ok_or(ContractError::TokenNotSettled {})?;
+            // The chain charges the denom creation fee to the contract, the caller covers it
+            let fee = denom_creation_fee(deps.querier)?;
+            let paid = |required: &Coin| {
+                info.funds
+                    .iter()
+                    .any(|coin| coin.denom == required.denom && coin.amount >= required.amount)
+            };
+            if !fee.iter().all(paid) {
+                let fee = fee.iter().map(Coin::to_string).collect::<Vec<_>>().join(",");
+                return Err(ContractError::DenomCreationFee { fee });
+            }
+            let contract = &env.contract.address;
+            let denom = factory_denom(contract, &state.token_info.symbol);
+            response = response
+                .add_message(create_denom_message(contract, &state.token_info.symbol))
+                .add_message(set_metadata_message(contract, &denom, &state.token_info, decimals))
+                .add_attribute("denom", denom.clone());
+            state.token_info.denom = Some(denom);
+        }
+        (SaleTokenKind::Cw20Minter { code_id: Some(code_id) }, None) => {
+            let decimals = state.token_info.decimals.ok_or(ContractError::TokenNotSettled {})?;
+            let msg = Cw20InstantiateMsg {
+                name: state.token_info.name.clone(),
+                symbol: state.token_info.symbol.clone(),
+                decimals,
+                initial_balances: vec![],
+                mint: Some(MinterResponse {
+                    minter: env.contract.address.to_string(),
+                    cap: Some(
+                        Uint128::from(state.token_info.supply) *
+                            Uint128::from(u64::pow(10, decimals as u32))
+                    ),
+                }),
+                marketing: None,
+            };
+            let instantiate = WasmMsg::Instantiate {
+                admin: Some(state.admin.to_string()),
+                code_id,
+                msg: to_json_binary(&msg)?,
+                funds: vec![],
+                label: format!("{} sale token", state.token_info.symbol),
+            };
+            response = response.add_submessage(
+                SubMsg::reply_on_success(instantiate, INSTANTIATE_TOKEN_REPLY_ID)
+            );
+        }
+        _ => {}
+    }
+
+    state.stage = SaleStage::Finalized;
+    STATE.save(deps.storage, &state)?;
+
+    Ok(response)
+}
+
+// Sends sale tokens out of the contract, launchpad issued tokens are minted instead
+fn sale_token_message(
+    env: &Env,
+    token_info: &TokenInfo,
+    denom: String,
+    amount: Uint128,
+    receiver: Addr
+) -> StdResult<CosmosMsg<InjectiveMsgWrapper>> {
+    match token_info.kind {
+        SaleTokenKind::TokenFactory =>
+            Ok(mint_message(&env.contract.address, Coin { denom, amount }, &receiver)),
+        SaleTokenKind::Cw20Minter { .. } =>
+            Ok(
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: denom,
+                    funds: vec![],
+                    msg: to_json_binary(
+                        &(Cw20ExecuteMsg::Mint {
+                            recipient: receiver.to_string(),
+                            amount,
+                        })
+                    )?,
+                })
+            ),
+        _ => transfer_token_message(denom, token_info.kind.token_type(), amount, receiver),
+    }
 }
 
-// fn airdrop_tokens(deps: DepsMut, _env: Env, info: MessageInfo) -> StdResult<Response> {
+fn cancel(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let mut state = STATE.load(deps.storage)?;
+    ensure_role(deps.as_ref(), &state, &info.sender, Role::Owner)?;
+
+    // Funds of a finalized sale may already be withdrawn, it can no longer refund
+    let status = state.status(&env);
+    if matches!(status, SaleStatus::Finalized | SaleStatus::Claimable | SaleStatus::Cancelled) {
+        return Err(invalid_status(status));
+    }
+
+    state.stage = SaleStage::Cancelled;
+    STATE.save(deps.storage, &state)?;
+
+    Ok(Response::new().add_attribute("action", "cancel"))
+}
+
+// Pauses with a reason, or resumes the sale when `reason` is None
+fn set_pause(
+    deps: DepsMut,
+    info: MessageInfo,
+    reason: Option<String>
+) -> Result<Response, ContractError> {
+    let mut state = STATE.load(deps.storage)?;
+    ensure_role(deps.as_ref(), &state, &info.sender, Role::Pauser)?;
+
+    if reason.is_none() && state.pause_reason.is_none() {
+        return Err(ContractError::NotPaused {});
+    }
+    state.pause_reason = reason.clone();
+    STATE.save(deps.storage, &state)?;
+
+    Ok(
+        Response::new()
+            .add_attribute("action", if reason.is_some() { "pause" } else { "unpause" })
+            .add_attribute("reason", reason.unwrap_or_default())
+    )
+}
+
+fn set_phase_pause(
+    deps: DepsMut,
+    info: MessageInfo,
+    name: String,
+    reason: Option<String>
+) -> Result<Response, ContractError> {
+    let mut state = STATE.load(deps.storage)?;
+    ensure_role(deps.as_ref(), &state, &info.sender, Role::Pauser)?;
+
+    let phase = state.phases
+        .iter_mut()
+        .find(|p| p.name == name)
+        .ok_or_else(|| ContractError::PhaseNotFound { name: name.clone() })?;
+    if reason.is_none() && phase.pause_reason.is_none() {
+        return Err(ContractError::NotPaused {});
+    }
+    phase.pause_reason = reason.clone();
+    STATE.save(deps.storage, &state)?;
+
+    Ok(
+        Response::new()
+            .add_attribute("action", if reason.is_some() { "pause_phase" } else { "unpause_phase" })
+            .add_attribute("phase_name", name)
+            .add_attribute("reason", reason.unwrap_or_default())
+    )
+}
+
+fn update_role(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+    role: Role,
+    granted: bool
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    ensure_role(deps.as_ref(), &state, &info.sender, Role::Owner)?;
+
+    let address = deps.api.addr_validate(&address)?;
+    let mut roles = ROLES.may_load(deps.storage, address.clone())?.unwrap_or_default();
+    roles.retain(|held| *held != role);
+    if granted {
+        roles.push(role.clone());
+    }
+    if roles.is_empty() {
+        ROLES.remove(deps.storage, address.clone());
+    } else {
+        ROLES.save(deps.storage, address.clone(), &roles)?;
+    }
+
+    Ok(
+        Response::new()
+            .add_attribute("action", if granted { "grant_role" } else { "revoke_role" })
+            .add_attribute("address", address.to_string())
+            .add_attribute("role", role.to_string())
+    )
+}
+
+fn propose_admin(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    address: String,
+    expiry: Option<u64>
+) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    ensure_role(deps.as_ref(), &state, &info.sender, Role::Owner)?;
+
+    if expiry.is_some_and(|expiry| expiry <= env.block.time.seconds()) {
+        return Err(ContractError::AdminProposalExpired {});
+    }
+
+    // A new proposal replaces the pending one
+    let address = deps.api.addr_validate(&address)?;
+    PENDING_ADMIN.save(deps.storage, &(PendingAdmin { address: address.clone(), expiry }))?;
+
+    Ok(
+        Response::new()
+            .add_attribute("action", "propose_admin")
+            .add_attribute("address", address.to_string())
+    )
+}
+
+fn accept_admin(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let pending = PENDING_ADMIN.may_load(deps.storage)?.ok_or(ContractError::NoPendingAdmin {})?;
+    if pending.address != info.sender {
+        return Err(ContractError::NotPendingAdmin {});
+    }
+    if pending.expiry.is_some_and(|expiry| env.block.time.seconds() > expiry) {
+        return Err(ContractError::AdminProposalExpired {});
+    }
+
+    let mut state = STATE.load(deps.storage)?;
+    let previous = state.admin;
+    state.admin = pending.address;
+    STATE.save(deps.storage, &state)?;
+    PENDING_ADMIN.remove(deps.storage);
+
+    Ok(
+        Response::new()
+            .add_attribute("action", "accept_admin")
+            .add_attribute("previous_admin", previous.to_string())
+            .add_attribute("admin", state.admin.to_string())
+    )
+}
+
+fn cancel_admin_proposal(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let state = STATE.load(deps.storage)?;
+    ensure_role(deps.as_ref(), &state, &info.sender, Role::Owner)?;
+
+    if PENDING_ADMIN.may_load(deps.storage)?.is_none() {
+        return Err(ContractError::NoPendingAdmin {});
+    }
+    PENDING_ADMIN.remove(deps.storage);
+
+    Ok(Response::new().add_attribute("action", "cancel_admin_proposal"))
+}
+
+// fn airdrop_tokens(deps: DepsMut, _env: Env, info: MessageInfo) -> Result<Response, ContractError> {
 //     let state = STATE.load(deps.storage)?;
 //     if state.admin != info.sender {
-//         return Err(StdError::generic_err("Unauthorized: not admin"));
+//         return Err(ContractError::Unauthorized {});
 //     }
 //     if !state.claimable {
 //         return Err(StdError::generic_err("Not in claimable period"));
@@ -696,25 +2243,79 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::GetLaunchpad {} => to_json_binary(&query_launchpad(deps, env)?),
         QueryMsg::GetUser { address } => to_json_binary(&query_user(deps, env, address)?),
+        QueryMsg::AllUsers { start_after, limit } =>
+            to_json_binary(&query_all_users(deps, env, start_after, limit)?),
+        QueryMsg::TopBuyers { start_after, limit } =>
+            to_json_binary(&query_top_buyers(deps, start_after, limit)?),
         QueryMsg::GetPhase { phase_name } => to_json_binary(&query_phase(deps, env, phase_name)?),
+        QueryMsg::GetFees {} => to_json_binary(&query_fees(deps)?),
+        QueryMsg::Status {} => to_json_binary(&query_status(deps, env)?),
+        QueryMsg::Whitelist { start_after, limit } =>
+            to_json_binary(&query_whitelist(deps, start_after, limit)?),
+        QueryMsg::PhaseAllowlist { phase_name, start_after, limit } =>
+            to_json_binary(&query_phase_allowlist(deps, phase_name, start_after, limit)?),
+        QueryMsg::PriceQuote { phase_name, amount } =>
+            to_json_binary(&query_price_quote(deps, env, phase_name, amount)?),
+        QueryMsg::SimulateDeposit {
+            address,
+            amount,
+            phase_proof,
+            whitelist_proof,
+            authorization,
+        } =>
+            to_json_binary(
+                &query_simulate_deposit(
+                    deps,
+                    env,
+                    address,
+                    amount,
+                    phase_proof,
+                    whitelist_proof,
+                    authorization
+                )?
+            ),
+        QueryMsg::Roles { start_after, limit } =>
+            to_json_binary(&query_roles(deps, start_after, limit)?),
+        QueryMsg::PendingAdmin {} => to_json_binary(&query_pending_admin(deps)?),
+        QueryMsg::ContractVersion {} => to_json_binary(&get_contract_version(deps.storage)?),
     }
 }
 
-fn query_launchpad(deps: Deps, _env: Env) -> StdResult<LaunchpadResponse> {
-    let state = STATE.load(deps.storage)?;
+fn query_launchpad(deps: Deps, env: Env) -> StdResult<LaunchpadResponse> {
+    let state = load_state(deps.storage, &env)?;
     let mut new_ph = Vec::new();
     for ph in state.phases.iter() {
+        let allowlist_size = PHASE_ALLOWLIST_SIZE.may_load(deps.storage, ph.name.clone())?;
         new_ph.push(PhaseResp {
             name: ph.name.clone(),
             start_time: ph.start_time,
             end_time: ph.end_time,
             price_per_token: ph.price_per_token,
             supply: ph.supply,
-            address_list: ph.address_list.len() as u64,
+            address_list: allowlist_size.unwrap_or(0),
             limit: ph.limit,
             total_minted: ph.total_minted,
+            total_raised: ph.total_raised,
+            merkle_root: ph.merkle_root.clone(),
+            signature_required: ph.signature_required,
+            mode: ph.mode.clone(),
+            total_committed: ph.total_committed,
+            clearing_price: ph.clearing_price,
+            pause_reason: ph.pause_reason.clone(),
+            rollover: ph.rollover.clone(),
         });
     }
+    let total_raised = state.total_raised();
+    let status = state.status(&env);
+    let unallocated_supply = Uint128::from(state.token_info.supply).saturating_sub(
+        state.phases_supply()
+    );
+    let required = state.escrow_required();
+    let escrow = EscrowResponse {
+        escrowed: state.escrowed,
+        required,
+        funded: required.is_some_and(|required| state.escrowed >= required),
+    };
     Ok(LaunchpadResponse {
         token_info: state.token_info,
         buy_denom: state.buy_denom,
@@ -726,74 +2327,327 @@ fn query_launchpad(deps: Deps, _env: Env) -> StdResult<LaunchpadResponse> {
         claimable: state.claimable,
         fees_wallet: state.fees_wallet,
         fees_percentage: state.fees_percentage,
+        fees_collected: state.fees_collected,
         withdraw_wallet: state.withdraw_wallet,
-        whitelist: state.whitelist,
+        withdrawn: state.withdrawn,
+        soft_cap: state.soft_cap,
+        total_raised,
+        vesting: state.vesting,
+        tge_time: state.tge_time,
+        whitelist_merkle_root: state.whitelist_merkle_root,
+        signer_pubkey: state.signer_pubkey,
+        status,
+        pause_reason: state.pause_reason,
+        unallocated_supply,
+        escrow,
     })
 }
 
-fn query_user(deps: Deps, _env: Env, address: Addr) -> StdResult<UserInfoResponse> {
+fn query_status(deps: Deps, env: Env) -> StdResult<StatusResponse> {
+    let state = STATE.load(deps.storage)?;
+    Ok(StatusResponse { status: state.status(&env), pause_reason: state.pause_reason })
+}
+
+// Pagination cursors are caller input, validated like any other address
+fn maybe_addr(deps: Deps, address: Option<String>) -> StdResult<Option<Addr>> {
+    address.map(|address| deps.api.addr_validate(&address)).transpose()
+}
+
+fn query_user(deps: Deps, env: Env, address: String) -> StdResult<UserInfoResponse> {
+    let address = deps.api.addr_validate(&address)?;
     let user = USER_INFO.load(deps.storage, address.clone()).unwrap_or_else(|_| User {
         buy_phases: HashMap::new(),
         amount: 0,
         tokens: Uint128::zero(),
-        claimed: false,
+        claimed_amount: Uint128::zero(),
+        paid: Uint128::zero(),
+        refunded: false,
+        committed: HashMap::new(),
+        bids: HashMap::new(),
     });
 
-    let mut phases = HashMap::new();
+    let state = load_state(deps.storage, &env)?;
 
-    let state = STATE.load(deps.storage)?;
+    user_info_response(deps, &state, &env, address, user)
+}
 
-    for ph in state.phases.iter() {
-        let mut is_eligible = ph.address_list
-            .iter()
-            .find(|&addr| addr == address)
-            .is_some();
+fn query_all_users(
+    deps: Deps,
+    env: Env,
+    start_after: Option<String>,
+    limit: Option<u32>
+) -> StdResult<AllUsersResponse> {
+    let state = load_state(deps.storage, &env)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = maybe_addr(deps, start_after)?.map(Bound::exclusive);
+
+    let users = USER_INFO.range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (address, user) = item?;
+            Ok((address.to_string(), user_info_response(deps, &state, &env, address, user)?))
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(AllUsersResponse { users })
+}
 
-        if ph.address_list.len() == 0 {
-            is_eligible = true;
+// Buyers sorted by tokens bought, largest first
+fn query_top_buyers(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>
+) -> StdResult<UserTokensResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let end = match maybe_addr(deps, start_after)? {
+        Some(address) => {
+            let user = users().load(deps.storage, address.clone())?;
+            Some(Bound::exclusive((user.tokens.u128(), address)))
         }
+        None => None,
+    };
 
-        let current_mint = user.buy_phases
-            .iter()
-            .find(|&ub| *ub.0 == ph.name)
-            .map(|ub| ub.1)
-            .unwrap_or(&0);
+    let users = users()
+        .idx.tokens.range(deps.storage, None, end, Order::Descending)
+        .take(limit)
+        .map(|item| item.map(|(_, user)| user))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(UserTokensResponse { users })
+}
+
+fn user_info_response(
+    deps: Deps,
+    state: &State,
+    env: &Env,
+    address: Addr,
+    user: User
+) -> StdResult<UserInfoResponse> {
+    let mut phases = HashMap::new();
+
+    for ph in state.phases.iter() {
+        let is_eligible = is_eligible(deps.storage, ph, &address)?;
+
+        let current_mint = user.buy_phases.get(&ph.name).copied().unwrap_or(0);
 
         phases.insert(ph.name.to_string(), PhaseInformation {
             limit: ph.limit,
-            current_mint: current_mint.clone(),
+            current_mint,
+            committed: user.committed.get(&ph.name).copied().unwrap_or(0),
             eligible: is_eligible,
         });
     }
 
+    // Vested but not yet claimed tokens, only known once the token decimals are set
+    let claimable_amount = match (state.claimable, state.token_info.decimals, state.tge_time) {
+        (true, Some(decimals), Some(tge_time)) => {
+            let total = user.tokens * Uint128::from(u64::pow(10, decimals as u32));
+            vested_amount(total, &state.vesting, tge_time, env.block.time.seconds()).saturating_sub(
+                user.claimed_amount
+            )
+        }
+        _ => Uint128::zero(),
+    };
+
     Ok(UserInfoResponse {
         address,
         phases,
         amount: user.amount,
         tokens: user.tokens,
-        claimed: user.claimed,
+        claimed_amount: user.claimed_amount,
+        claimable_amount,
+        paid: user.paid,
+        refunded: user.refunded,
     })
 }
 
-fn query_phase(deps: Deps, _env: Env, phase_name: String) -> StdResult<PhaseInfoResponse> {
-    let state = STATE.load(deps.storage)?;
+fn query_phase(deps: Deps, env: Env, phase_name: String) -> StdResult<PhaseInfoResponse> {
+    let state = load_state(deps.storage, &env)?;
 
     // Find the phase with the matching name
     let phase = state.phases.iter().find(|&p| p.name == phase_name);
 
     match phase {
         Some(phase) => {
+            let allowlist_size = PHASE_ALLOWLIST_SIZE.may_load(deps.storage, phase.name.clone())?;
             Ok(PhaseInfoResponse {
                 name: phase.name.clone(), // Assuming PhaseInfoResponse expects a name field
                 start_time: phase.start_time,
                 end_time: phase.end_time,
                 price_per_token: phase.price_per_token,
                 supply: phase.supply,
-                address_list: phase.address_list.clone(),
+                address_list: allowlist_size.unwrap_or(0),
                 limit: phase.limit,
                 total_minted: phase.total_minted,
+                total_raised: phase.total_raised,
+                merkle_root: phase.merkle_root.clone(),
+                signature_required: phase.signature_required,
+                mode: phase.mode.clone(),
+                total_committed: phase.total_committed,
+                clearing_price: phase.clearing_price,
+                pause_reason: phase.pause_reason.clone(),
+                rollover: phase.rollover.clone(),
             })
         }
         None => Err(StdError::generic_err("Phase not found")),
     }
 }
+
+fn query_fees(deps: Deps) -> StdResult<FeesResponse> {
+    let state = STATE.load(deps.storage)?;
+    Ok(FeesResponse {
+        fees_wallet: state.fees_wallet,
+        fees_percentage: state.fees_percentage,
+        fees_collected: state.fees_collected,
+    })
+}
+
+fn query_whitelist(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>
+) -> StdResult<WhitelistResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = maybe_addr(deps, start_after)?.map(Bound::exclusive);
+
+    let whitelist = WHITELIST.range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(address, amount)| WhitelistUser { address, amount }))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(WhitelistResponse { whitelist })
+}
+
+fn query_roles(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>
+) -> StdResult<RolesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = maybe_addr(deps, start_after)?.map(Bound::exclusive);
+
+    let holders = ROLES.range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(address, roles)| RoleHolder { address, roles }))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(RolesResponse { holders })
+}
+
+fn query_pending_admin(deps: Deps) -> StdResult<PendingAdminResponse> {
+    Ok(PendingAdminResponse { pending_admin: PENDING_ADMIN.may_load(deps.storage)? })
+}
+
+fn query_phase_allowlist(
+    deps: Deps,
+    phase_name: String,
+    start_after: Option<String>,
+    limit: Option<u32>
+) -> StdResult<AllowlistResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = maybe_addr(deps, start_after)?.map(Bound::exclusive);
+
+    let addresses = PHASE_ALLOWLIST.prefix(allowlist_prefix(deps.storage, &phase_name)?)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(AllowlistResponse { addresses })
+}
+
+fn query_price_quote(
+    deps: Deps,
+    env: Env,
+    phase_name: String,
+    amount: u64
+) -> StdResult<PriceQuoteResponse> {
+    let state = load_state(deps.storage, &env)?;
+    let phase = state.phases
+        .iter()
+        .find(|p| p.name == phase_name)
+        .ok_or_else(|| StdError::generic_err("Phase not found"))?;
+
+    let current_time = env.block.time.seconds();
+    Ok(PriceQuoteResponse {
+        price_per_token: phase.cost(1, current_time),
+        total_price: phase.cost(amount, current_time),
+    })
+}
+
+fn query_simulate_deposit(
+    deps: Deps,
+    env: Env,
+    address: String,
+    amount: u64,
+    phase_proof: Option<AllocationProof>,
+    whitelist_proof: Option<AllocationProof>,
+    authorization: Option<PurchaseAuthorization>
+) -> StdResult<SimulateDepositResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let state = load_state(deps.storage, &env)?;
+    let tokens = Uint128::from(amount) * state.tokens_per_buy;
+    let plan = plan_deposit(
+        deps,
+        &env,
+        &state,
+        &address,
+        amount,
+        phase_proof,
+        whitelist_proof,
+        authorization
+    );
+
+    let plan = match plan {
+        Ok(plan) => plan,
+        Err(ContractError::Std(err)) => {
+            return Err(err);
+        }
+        Err(err) => {
+            let phase = current_phase(env.block.time.seconds(), state.phases);
+            return Ok(SimulateDepositResponse {
+                phase: phase.map(|phase| phase.name),
+                required_funds: Coin { denom: state.buy_denom, amount: Uint128::zero() },
+                tokens,
+                remaining_supply: 0,
+                remaining_phase_allowance: 0,
+                whitelist_allowance: 0,
+                rejection: Some(deposit_rejection(&err)),
+                error: Some(err.to_string()),
+            });
+        }
+    };
+
+    let result = validate_deposit(&plan, amount);
+    Ok(SimulateDepositResponse {
+        phase: Some(plan.phase.name.clone()),
+        required_funds: Coin { denom: state.buy_denom, amount: plan.cost },
+        tokens,
+        remaining_supply: plan.remaining_supply(),
+        remaining_phase_allowance: plan.phase.limit.saturating_sub(plan.bought()),
+        whitelist_allowance: plan.wl_user,
+        rejection: result.as_ref().err().map(deposit_rejection),
+        error: result.err().map(|err| err.to_string()),
+    })
+}
+
+fn deposit_rejection(err: &ContractError) -> DepositRejection {
+    match err {
+        ContractError::SaleNotStarted {} => DepositRejection::SaleNotStarted,
+        ContractError::SaleEnded {} => DepositRejection::SaleEnded,
+        ContractError::NoActivePhase {} => DepositRejection::NoActivePhase,
+        ContractError::NotInPhase { .. } => DepositRejection::NotInPhase,
+        ContractError::AuthorizationRequired { .. } => DepositRejection::AuthorizationRequired,
+        ContractError::InvalidSignature {} => DepositRejection::InvalidSignature,
+        ContractError::AuthorizationExpired {} => DepositRejection::AuthorizationExpired,
+        ContractError::NonceUsed { .. } => DepositRejection::NonceUsed,
+        ContractError::InvalidMerkleProof {} => DepositRejection::InvalidMerkleProof,
+        ContractError::Overbuy {} => DepositRejection::Overbuy,
+        ContractError::ExceedsLimit { .. } => DepositRejection::ExceedsLimit,
+        ContractError::PhaseLimitReached { .. } => DepositRejection::PhaseLimitReached,
+        ContractError::Paused { .. } => DepositRejection::Paused,
+        ContractError::PhasePaused { .. } => DepositRejection::Paused,
+        ContractError::InvalidStatus { .. } => DepositRejection::InvalidStatus,
+        ContractError::Underfunded { .. } => DepositRejection::Underfunded,
+        _ => DepositRejection::Other,
+    }
+}
@@ -0,0 +1,254 @@
+use cosmwasm_std::{ StdError, Uint128 };
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    /// Kept for clients matching on it, privileged handlers check roles and report `MissingRole`
+    /// since the admin is no longer the only account allowed to call them
+    #[error("Unauthorized: not admin")]
+    Unauthorized {},
+
+    #[error("Unauthorized: missing role {role}")]
+    MissingRole {
+        role: String,
+    },
+
+    #[error("Unauthorized: not the pending admin")]
+    NotPendingAdmin {},
+
+    #[error("No pending admin")]
+    NoPendingAdmin {},
+
+    #[error("Admin proposal expired")]
+    AdminProposalExpired {},
+
+    #[error("Unauthorized: launchpad already started")]
+    SaleStarted {},
+
+    #[error("Launchpad not started")]
+    SaleNotStarted {},
+
+    #[error("Launchpad ended")]
+    SaleEnded {},
+
+    #[error("Launchpad not ended")]
+    SaleNotEnded {},
+
+    #[error("Not allowed while the sale is {status}")]
+    InvalidStatus {
+        status: String,
+    },
+
+    #[error("Launchpad paused: {reason}")]
+    Paused {
+        reason: String,
+    },
+
+    #[error("Phase {phase} paused: {reason}")]
+    PhasePaused {
+        phase: String,
+        reason: String,
+    },
+
+    #[error("Not paused")]
+    NotPaused {},
+
+    #[error("Start time must be before end time")]
+    InvalidTimeRange {},
+
+    #[error("Fees percentage must be <= {max}")]
+    InvalidFeesPercentage {
+        max: u64,
+    },
+
+    #[error("TGE unlock percentage must be <= 10000")]
+    InvalidTgeUnlock {},
+
+    #[error("Vesting period must be > 0 and <= duration")]
+    InvalidVestingPeriod {},
+
+    #[error("Vesting already started")]
+    VestingStarted {},
+
+    #[error("Token info can't change once claims opened")]
+    TokenInfoLocked {},
+
+    #[error("Sale token can't change once escrowed")]
+    SaleTokenLocked {},
+
+    #[error("Finalizing must pay the {fee} denom creation fee")]
+    DenomCreationFee {
+        fee: String,
+    },
+
+    #[error("Phase supply must be > 0")]
+    InvalidPhaseSupply {},
+
+    #[error("Phases sell {allocated} tokens, above the token sale supply of {supply}")]
+    SupplyExceeded {
+        allocated: Uint128,
+        supply: u64,
+    },
+
+    #[error("Phase supply can't go below the {sold} already sold")]
+    SupplyBelowSold {
+        sold: u64,
+    },
+
+    #[error("Phase price must be > 0")]
+    InvalidPhasePrice {},
+
+    #[error("Phase sales counters must start at zero")]
+    InvalidPhaseCounters {},
+
+    #[error("Phase {phase} price can't change once commitments were made")]
+    PriceLocked {
+        phase: String,
+    },
+
+    #[error("Auction floor price must be > 0 and <= start price, step must be > 0")]
+    InvalidAuction {},
+
+    #[error("Price tiers must not be empty, tier supply and price must be > 0")]
+    InvalidTiers {},
+
+    #[error("Phase {name} already exists")]
+    PhaseExists {
+        name: String,
+    },
+
+    #[error("Phase {name} not found")]
+    PhaseNotFound {
+        name: String,
+    },
+
+    #[error("No active phase")]
+    NoActivePhase {},
+
+    #[error("Unauthorized: not in phase {phase}")]
+    NotInPhase {
+        phase: String,
+    },
+
+    #[error("Invalid amount: overbuy")]
+    Overbuy {},
+
+    #[error("Invalid amount, tried to buy {amount} over {limit} limit")]
+    ExceedsLimit {
+        amount: u64,
+        limit: u64,
+    },
+
+    #[error("Max buy phase ({phase}) reached")]
+    PhaseLimitReached {
+        phase: String,
+    },
+
+    #[error("Payment Failed, expected {expected}, got {got}")]
+    WrongPayment {
+        expected: Uint128,
+        got: Uint128,
+    },
+
+    #[error("Payment Failed, expected {expected} token")]
+    WrongTokenType {
+        expected: String,
+    },
+
+    #[error("Unauthorized: invalid cw20 token")]
+    InvalidCw20Token {},
+
+    #[error("Soft cap not reached")]
+    SoftCapNotReached {},
+
+    #[error("Invalid merkle root")]
+    InvalidMerkleRoot {},
+
+    #[error("Invalid merkle proof")]
+    InvalidMerkleProof {},
+
+    #[error("Purchase authorization required in phase {phase}")]
+    AuthorizationRequired {
+        phase: String,
+    },
+
+    #[error("Invalid purchase authorization signature")]
+    InvalidSignature {},
+
+    #[error("Purchase authorization expired")]
+    AuthorizationExpired {},
+
+    #[error("Nonce {nonce} already used")]
+    NonceUsed {
+        nonce: u64,
+    },
+
+    #[error("Nothing to settle")]
+    NothingToSettle {},
+
+    #[error("Pro-rata commitments must be settled first")]
+    NotSettled {},
+
+    #[error("No ended phase to roll over")]
+    NothingToRollOver {},
+
+    #[error("Nothing to withdraw")]
+    NothingToWithdraw {},
+
+    #[error("Token denom not settled")]
+    TokenNotSettled {},
+
+    #[error("Funds must be the sale token")]
+    WrongSaleToken {},
+
+    #[error("Sale token escrow {escrowed} below the required {required}")]
+    Underfunded {
+        escrowed: Uint128,
+        required: Uint128,
+    },
+
+    #[error("Unknown reply id {id}")]
+    UnknownReply {
+        id: u64,
+    },
+
+    #[error("Sale token address missing from the instantiate reply")]
+    MissingTokenAddress {},
+
+    #[error("Not claimable")]
+    NotClaimable {},
+
+    #[error("User not found")]
+    UserNotFound {},
+
+    #[error("Already claimed")]
+    AlreadyClaimed {},
+
+    #[error("Nothing to claim yet")]
+    NothingToClaim {},
+
+    #[error("Refund not available")]
+    RefundNotAvailable {},
+
+    #[error("Already refunded")]
+    AlreadyRefunded {},
+
+    #[error("Cannot migrate from contract {contract}")]
+    InvalidMigration {
+        contract: String,
+    },
+
+    #[error("Cannot migrate from version {from} to older version {to}")]
+    CannotDowngrade {
+        from: String,
+        to: String,
+    },
+
+    #[error("Invalid contract version {version}")]
+    InvalidVersion {
+        version: String,
+    },
+}
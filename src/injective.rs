@@ -0,0 +1,151 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    Addr,
+    Coin,
+    CosmosMsg,
+    CustomMsg,
+    CustomQuery,
+    QuerierWrapper,
+    QueryRequest,
+    StdResult,
+};
+
+use crate::state::TokenInfo;
+
+// Injective custom messages, serialized like the chain bindings expect them
+#[cw_serde]
+pub struct InjectiveMsgWrapper {
+    pub route: InjectiveRoute,
+    pub msg_data: InjectiveMsg,
+}
+
+impl CustomMsg for InjectiveMsgWrapper {}
+
+// Injective custom queries, answered by the chain bindings
+#[cw_serde]
+pub struct InjectiveQueryWrapper {
+    pub route: InjectiveRoute,
+    pub query_data: InjectiveQuery,
+}
+
+impl CustomQuery for InjectiveQueryWrapper {}
+
+#[cw_serde]
+pub enum InjectiveRoute {
+    Tokenfactory,
+}
+
+#[cw_serde]
+pub enum InjectiveMsg {
+    /// Creates `factory/<sender>/<subdenom>`, administered by the sender
+    CreateDenom {
+        sender: String,
+        subdenom: String,
+    },
+    SetDenomMetadata {
+        sender: String,
+        metadata: DenomMetadata,
+    },
+    Mint {
+        sender: String,
+        amount: Coin,
+        mint_to: String,
+    },
+}
+
+#[cw_serde]
+pub enum InjectiveQuery {
+    /// Fee charged to the creator of a tokenfactory denom
+    TokenFactoryDenomCreationFee {},
+}
+
+#[cw_serde]
+pub struct TokenFactoryCreateDenomFeeResponse {
+    pub fee: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct DenomMetadata {
+    pub description: String,
+    pub denom_units: Vec<DenomUnit>,
+    pub base: String,
+    pub display: String,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub uri_hash: String,
+    pub decimals: u32,
+}
+
+#[cw_serde]
+pub struct DenomUnit {
+    pub denom: String,
+    pub exponent: u32,
+    pub aliases: Vec<String>,
+}
+
+pub fn factory_denom(contract: &Addr, subdenom: &str) -> String {
+    format!("factory/{}/{}", contract, subdenom)
+}
+
+pub fn denom_creation_fee(querier: QuerierWrapper<InjectiveQueryWrapper>) -> StdResult<Vec<Coin>> {
+    let request = QueryRequest::Custom(InjectiveQueryWrapper {
+        route: InjectiveRoute::Tokenfactory,
+        query_data: InjectiveQuery::TokenFactoryDenomCreationFee {},
+    });
+    let response: TokenFactoryCreateDenomFeeResponse = querier.query(&request)?;
+    Ok(response.fee)
+}
+
+fn tokenfactory_message(msg_data: InjectiveMsg) -> CosmosMsg<InjectiveMsgWrapper> {
+    CosmosMsg::Custom(InjectiveMsgWrapper { route: InjectiveRoute::Tokenfactory, msg_data })
+}
+
+pub fn create_denom_message(contract: &Addr, subdenom: &str) -> CosmosMsg<InjectiveMsgWrapper> {
+    tokenfactory_message(InjectiveMsg::CreateDenom {
+        sender: contract.to_string(),
+        subdenom: subdenom.to_string(),
+    })
+}
+
+// Bank metadata of the sale token, displayed in whole tokens of `decimals` base units
+pub fn set_metadata_message(
+    contract: &Addr,
+    denom: &str,
+    token_info: &TokenInfo,
+    decimals: u8
+) -> CosmosMsg<InjectiveMsgWrapper> {
+    tokenfactory_message(InjectiveMsg::SetDenomMetadata {
+        sender: contract.to_string(),
+        metadata: DenomMetadata {
+            description: token_info.description.clone(),
+            denom_units: vec![
+                DenomUnit { denom: denom.to_string(), exponent: 0, aliases: vec![] },
+                DenomUnit {
+                    denom: token_info.symbol.clone(),
+                    exponent: decimals as u32,
+                    aliases: vec![],
+                }
+            ],
+            base: denom.to_string(),
+            display: token_info.symbol.clone(),
+            name: token_info.name.clone(),
+            symbol: token_info.symbol.clone(),
+            uri: String::new(),
+            uri_hash: String::new(),
+            decimals: decimals as u32,
+        },
+    })
+}
+
+pub fn mint_message(
+    contract: &Addr,
+    amount: Coin,
+    receiver: &Addr
+) -> CosmosMsg<InjectiveMsgWrapper> {
+    tokenfactory_message(InjectiveMsg::Mint {
+        sender: contract.to_string(),
+        amount,
+        mint_to: receiver.to_string(),
+    })
+}
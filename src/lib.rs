@@ -1,5 +1,9 @@
 pub mod contract;
+pub mod error;
+pub mod injective;
+pub mod merkle;
+pub mod migrations;
 pub mod msg;
 pub mod state;
 pub mod utils;
-// mod test;
+mod test;
@@ -0,0 +1,85 @@
+use cosmwasm_std::Addr;
+use sha2::{ Digest, Sha256 };
+
+pub type Hash = [u8; 32];
+
+// Leaves commit to an address and its allocation, the allocation as big endian bytes
+pub fn leaf_hash(address: &str, allocation: u64) -> Hash {
+    let mut hasher = Sha256::new();
+    hasher.update(address.as_bytes());
+    hasher.update(allocation.to_be_bytes());
+    hasher.finalize().into()
+}
+
+// Pairs are sorted before hashing so proofs don't need to carry the node side
+pub fn hash_pair(a: &Hash, b: &Hash) -> Hash {
+    let (left, right) = if a <= b { (a, b) } else { (b, a) };
+    let mut hasher = Sha256::new();
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+pub fn parse_hash(hex_hash: &str) -> Option<Hash> {
+    hex::decode(hex_hash).ok()?.try_into().ok()
+}
+
+// Checks a hex encoded proof of `(address, allocation)` against a hex encoded root
+pub fn verify_proof(root: &str, address: &Addr, allocation: u64, proof: &[String]) -> bool {
+    let root = match parse_hash(root) {
+        Some(root) => root,
+        None => {
+            return false;
+        }
+    };
+
+    let mut hash = leaf_hash(address.as_str(), allocation);
+    for sibling in proof {
+        match parse_hash(sibling) {
+            Some(sibling) => {
+                hash = hash_pair(&hash, &sibling);
+            }
+            None => {
+                return false;
+            }
+        }
+    }
+    hash == root
+}
+
+// Off-chain tree used to publish the root and hand out proofs
+pub struct MerkleTree {
+    levels: Vec<Vec<Hash>>,
+}
+
+impl MerkleTree {
+    // An unpaired node is carried up to the next level unchanged
+    pub fn new(leaves: Vec<Hash>) -> Self {
+        let mut levels = vec![leaves];
+        while levels.last().is_some_and(|level| level.len() > 1) {
+            let next = levels
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| if pair.len() == 2 { hash_pair(&pair[0], &pair[1]) } else { pair[0] })
+                .collect();
+            levels.push(next);
+        }
+        MerkleTree { levels }
+    }
+
+    pub fn root(&self) -> Option<Hash> {
+        self.levels.last().and_then(|level| level.first().copied())
+    }
+
+    pub fn proof(&self, mut index: usize) -> Vec<Hash> {
+        let mut proof = vec![];
+        for level in self.levels.iter().take(self.levels.len() - 1) {
+            if let Some(sibling) = level.get(index ^ 1) {
+                proof.push(*sibling);
+            }
+            index /= 2;
+        }
+        proof
+    }
+}
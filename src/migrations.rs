@@ -0,0 +1,220 @@
+use std::collections::HashMap;
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{ Addr, DepsMut, Env, Order, StdResult, Uint128 };
+use cw_storage_plus::{ Item, Map };
+
+use crate::injective::InjectiveQueryWrapper;
+use crate::state::{
+    Phase,
+    PhaseMode,
+    RolloverPolicy,
+    SaleStage,
+    State,
+    TokenInfo,
+    User,
+    UserToken,
+    WhitelistUser,
+    users,
+    PHASE_ALLOWLIST,
+    PHASE_ALLOWLIST_SIZE,
+    STATE_KEY,
+    STATE,
+    USER_INFO,
+    WHITELIST,
+};
+use crate::utils::get_token_amount;
+
+// Storage layout of the 1.0 launchpads deployed before contract versioning
+#[cw_serde]
+struct StateV1 {
+    admin: Addr,
+    token_info: TokenInfo,
+    buy_denom: String,
+    buy_token_type: String,
+    tokens_per_buy: Uint128,
+    start_time: u64,
+    end_time: u64,
+    phases: Vec<PhaseV1>,
+    claimable: bool,
+    fees_wallet: Addr,
+    fees_percentage: u64,
+    withdraw_wallet: Addr,
+    whitelist: Vec<WhitelistUser>,
+}
+
+#[cw_serde]
+struct PhaseV1 {
+    name: String,
+    start_time: u64,
+    end_time: u64,
+    price_per_token: Uint128,
+    supply: u64,
+    address_list: Vec<Addr>,
+    limit: u64,
+    total_minted: u64,
+}
+
+#[cw_serde]
+struct UserV1 {
+    buy_phases: HashMap<String, u64>,
+    amount: u64,
+    tokens: Uint128,
+    claimed: bool,
+}
+
+const STATE_V1: Item<StateV1> = Item::new(STATE_KEY);
+const USER_INFO_V1: Map<Addr, UserV1> = Map::new("users");
+const USERS_V1: Map<String, Vec<UserToken>> = Map::new("user_tokens");
+
+pub fn migrate_from_v1_0(deps: DepsMut<InjectiveQueryWrapper>, env: &Env) -> StdResult<()> {
+    let storage = deps.storage;
+    let legacy = STATE_V1.load(storage)?;
+
+    // Allowlists moved out of the state into their own maps
+    for wl in legacy.whitelist {
+        WHITELIST.save(storage, wl.address, &wl.amount)?;
+    }
+    for phase in legacy.phases.iter() {
+        // 1.0 appended carried over allowlists without checking for repeats
+        let mut addresses = phase.address_list.clone();
+        addresses.sort();
+        addresses.dedup();
+        for address in addresses.iter() {
+            PHASE_ALLOWLIST.save(storage, (phase.name.clone(), 0, address.clone()), &())?;
+        }
+        PHASE_ALLOWLIST_SIZE.save(storage, phase.name.clone(), &(addresses.len() as u64))?;
+    }
+
+    let mut phases: Vec<Phase> = legacy.phases
+        .into_iter()
+        .map(|phase| Phase {
+            total_raised: phase.price_per_token * Uint128::from(phase.total_minted),
+            name: phase.name,
+            start_time: phase.start_time,
+            end_time: phase.end_time,
+            price_per_token: phase.price_per_token,
+            supply: phase.supply,
+            limit: phase.limit,
+            total_minted: phase.total_minted,
+            merkle_root: None,
+            signature_required: false,
+            mode: PhaseMode::FixedPrice,
+            total_committed: 0,
+            clearing_price: None,
+            pause_reason: None,
+            // 1.0 always carried the unsold supply and allowlist into the next phase
+            rollover: RolloverPolicy::CarrySupplyAndAllowlist,
+            rolled_over: false,
+            inherited_allowlists: vec![],
+        })
+        .collect();
+
+    // A next phase that already sold received the carry on its first purchase
+    for index in 1..phases.len() {
+        if phases[index].total_minted > 0 {
+            let previous = &mut phases[index - 1];
+            previous.supply = previous.total_minted;
+            previous.rolled_over = true;
+        }
+    }
+
+    // 1.0 only tracked a claimed flag, claims always released the full allocation
+    let unit = legacy.token_info.decimals.map(|decimals| u64::pow(10, decimals as u32));
+
+    let legacy_users = USER_INFO_V1.range(storage, None, None, Order::Ascending).collect::<
+        StdResult<Vec<_>>
+    >()?;
+
+    let mut total_claimed = Uint128::zero();
+    for (address, legacy_user) in legacy_users {
+        // What a buyer paid is rebuilt from the fixed price of each phase they bought in
+        let paid = phases
+            .iter()
+            .map(|phase| {
+                let bought = legacy_user.buy_phases.get(&phase.name).copied().unwrap_or(0);
+                phase.price_per_token * Uint128::from(bought)
+            })
+            .sum();
+        let claimed_amount = match (legacy_user.claimed, unit) {
+            (true, Some(unit)) => legacy_user.tokens * Uint128::from(unit),
+            _ => Uint128::zero(),
+        };
+        total_claimed += claimed_amount;
+
+        // The legacy vector double counted repeated purchases, rebuild it from the users
+        users().save(storage, address.clone(), &(UserToken {
+            address: address.clone(),
+            tokens: legacy_user.tokens,
+        }))?;
+
+        USER_INFO.save(storage, address, &(User {
+            buy_phases: legacy_user.buy_phases,
+            amount: legacy_user.amount,
+            tokens: legacy_user.tokens,
+            claimed_amount,
+            paid,
+            refunded: false,
+            committed: HashMap::new(),
+            bids: HashMap::new(),
+        }))?;
+    }
+    USERS_V1.remove(storage, "user_tokens".to_string());
+
+    // Claims already opened on a 1.0 sale are treated as a TGE without vesting
+    let tge_time = if legacy.claimable { Some(0) } else { None };
+
+    // 1.0 withdrawals were not recorded, whatever was raised and is no longer held went out
+    let total_raised: Uint128 = phases
+        .iter()
+        .map(|phase| phase.total_raised)
+        .sum();
+    let balance = get_token_amount(
+        deps.querier,
+        legacy.buy_denom.clone(),
+        env.contract.address.clone(),
+        legacy.buy_token_type.clone()
+    )?;
+    let withdrawn = total_raised.saturating_sub(balance);
+
+    // The sale tokens held plus those already claimed were escrowed before the upgrade
+    let escrowed = match (&legacy.token_info.denom, legacy.token_info.kind.is_minted()) {
+        (Some(denom), false) => {
+            let held = get_token_amount(
+                deps.querier,
+                denom.clone(),
+                env.contract.address.clone(),
+                legacy.token_info.kind.token_type()
+            )?;
+            held + total_claimed
+        }
+        _ => Uint128::zero(),
+    };
+
+    STATE.save(storage, &(State {
+        admin: legacy.admin,
+        token_info: legacy.token_info,
+        buy_denom: legacy.buy_denom,
+        buy_token_type: legacy.buy_token_type,
+        tokens_per_buy: legacy.tokens_per_buy,
+        start_time: legacy.start_time,
+        end_time: legacy.end_time,
+        phases,
+        claimable: legacy.claimable,
+        fees_wallet: legacy.fees_wallet,
+        fees_percentage: legacy.fees_percentage,
+        fees_collected: Uint128::zero(),
+        withdraw_wallet: legacy.withdraw_wallet,
+        withdrawn,
+        total_claimed,
+        escrowed,
+        soft_cap: None,
+        vesting: None,
+        tge_time,
+        whitelist_merkle_root: None,
+        signer_pubkey: None,
+        // Claims are only opened on finalized sales
+        stage: if legacy.claimable { SaleStage::Finalized } else { SaleStage::Open },
+        pause_reason: None,
+    }))
+}
@@ -1,13 +1,28 @@
 use std::collections::HashMap;
 
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{ Addr, Uint128 };
+use cosmwasm_std::{ Addr, Binary, Coin, Uint128 };
+use cw20::Cw20ReceiveMsg;
 
-use crate::state::{ Phase, PhaseInformation, PhaseResp, TokenInfo, WhitelistUser };
+use crate::state::{
+    PendingAdmin,
+    Phase,
+    PhaseInformation,
+    PhaseMode,
+    PhaseResp,
+    Role,
+    RoleHolder,
+    RolloverPolicy,
+    SaleStatus,
+    TokenInfo,
+    UserToken,
+    VestingConfig,
+    WhitelistUser,
+};
 
 #[cw_serde]
 pub struct InstantiateMsg {
-    pub admin: Addr,
+    pub admin: String,
     pub start_time: u64,
     pub end_time: u64,
     pub buy_denom: String,
@@ -15,12 +30,49 @@ pub struct InstantiateMsg {
     pub tokens_per_buy: Uint128,
     pub token_info: TokenInfo,
     pub phases: Option<Vec<Phase>>,
-    pub fees_wallet: Addr,
-    pub withdraw_wallet: Addr,
+    pub fees_wallet: String,
+    pub fees_percentage: Option<u64>,
+    pub withdraw_wallet: String,
     pub whitelist: Option<Vec<WhitelistUser>>,
+    pub soft_cap: Option<Uint128>,
+    pub vesting: Option<VestingConfig>,
+    pub whitelist_merkle_root: Option<String>,
+    pub signer_pubkey: Option<Binary>,
 }
 
 #[cw_serde]
+pub struct MigrateMsg {}
+
+// Merkle proof of an allocation, proof nodes are hex encoded
+#[cw_serde]
+pub struct AllocationProof {
+    pub allocation: u64,
+    pub proof: Vec<String>,
+}
+
+// Backend approval to buy up to `max_amount` in a phase, single use through its nonce
+#[cw_serde]
+pub struct PurchaseAuthorization {
+    pub max_amount: u64,
+    pub expiry: u64,
+    pub nonce: u64,
+    /// 64 bytes secp256k1 signature (r, s) of the sha256 of the json `AuthorizationPayload`
+    pub signature: Binary,
+}
+
+// Message signed by the backend, serialized as json with the fields in this order
+#[cw_serde]
+pub struct AuthorizationPayload {
+    pub contract: String,
+    pub buyer: String,
+    pub phase: String,
+    pub max_amount: u64,
+    pub expiry: u64,
+    pub nonce: u64,
+}
+
+#[cw_serde]
+#[allow(clippy::large_enum_variant)]
 pub enum ExecuteMsg {
     UpdateConfig {
         start_time: Option<u64>,
@@ -30,7 +82,10 @@ pub enum ExecuteMsg {
         tokens_per_buy: Option<Uint128>,
         token_info: Option<TokenInfo>,
         phases: Option<Vec<Phase>>,
-        new_admin: Option<Addr>,
+        soft_cap: Option<Uint128>,
+        vesting: Option<VestingConfig>,
+        whitelist_merkle_root: Option<String>,
+        signer_pubkey: Option<Binary>,
     },
     AddPhase {
         phase: Phase,
@@ -41,8 +96,8 @@ pub enum ExecuteMsg {
         end_time: Option<u64>,
         price_per_token: Option<Uint128>,
         supply: Option<u64>,
-        address_list: Option<Vec<Addr>>,
         limit: Option<u64>,
+        merkle_root: Option<String>,
     },
     RemovePhase {
         name: String,
@@ -51,17 +106,81 @@ pub enum ExecuteMsg {
         whitelist: Vec<WhitelistUser>,
     },
     RemoveFromWhitelist {
-        address: Addr,
+        addresses: Vec<String>,
+    },
+    AddToPhaseAllowlist {
+        phase_name: String,
+        addresses: Vec<String>,
+    },
+    RemoveFromPhaseAllowlist {
+        phase_name: String,
+        addresses: Vec<String>,
     },
     Deposit {
         amount: u64,
+        phase_proof: Option<AllocationProof>,
+        whitelist_proof: Option<AllocationProof>,
+        authorization: Option<PurchaseAuthorization>,
     },
+    Receive(Cw20ReceiveMsg),
     FlipClaimStatus {},
     ClaimTokens {},
-    Withdraw {
-        denom: String,
-        token_type: String,
+    ClaimRefund {},
+    /// Settles ended pro-rata phases for `address` (the sender by default), refunding the excess
+    Settle {
+        address: Option<String>,
+    },
+    Withdraw {},
+    WithdrawUnsold {},
+    /// Applies the rollover policy of the ended phases, also run by `Finalize`. Deposits only
+    /// carry supply and allowlists, burned or returned supply waits for one of these
+    RolloverPhases {},
+    /// Escrows the native sale tokens sent along, cw20 sale tokens are funded through `Receive`
+    FundSale {},
+    /// Closes a sale that ended above the soft cap, releasing the raised funds
+    Finalize {},
+    /// Aborts the sale before it is finalized, every buyer can claim a refund
+    Cancel {},
+    /// Halts deposits and claims until `Unpause`
+    Pause {
+        reason: String,
     },
+    Unpause {},
+    /// Halts deposits in the phase and claims of its buyers until `UnpausePhase`
+    PausePhase {
+        name: String,
+        reason: String,
+    },
+    UnpausePhase {
+        name: String,
+    },
+    GrantRole {
+        address: String,
+        role: Role,
+    },
+    RevokeRole {
+        address: String,
+        role: Role,
+    },
+    /// Starts an admin transfer, effective once `address` accepts it
+    ProposeAdmin {
+        address: String,
+        expiry: Option<u64>,
+    },
+    AcceptAdmin {},
+    CancelAdminProposal {},
+}
+
+// Hook messages embedded in a cw20 Send to the launchpad
+#[cw_serde]
+pub enum ReceiveMsg {
+    Deposit {
+        amount: u64,
+        phase_proof: Option<AllocationProof>,
+        whitelist_proof: Option<AllocationProof>,
+        authorization: Option<PurchaseAuthorization>,
+    },
+    FundSale {},
 }
 
 #[cw_serde]
@@ -69,13 +188,21 @@ pub struct AllUsersResponse {
     pub users: Vec<(String, UserInfoResponse)>, // Pair of user address and user info
 }
 
+#[cw_serde]
+pub struct UserTokensResponse {
+    pub users: Vec<UserToken>,
+}
+
 #[cw_serde]
 pub struct UserInfoResponse {
     pub address: Addr,
     pub phases: HashMap<String, PhaseInformation>,
     pub amount: u64,
     pub tokens: Uint128,
-    pub claimed: bool,
+    pub claimed_amount: Uint128,
+    pub claimable_amount: Uint128,
+    pub paid: Uint128,
+    pub refunded: bool,
 }
 
 #[cw_serde]
@@ -90,8 +217,35 @@ pub struct LaunchpadResponse {
     pub claimable: bool,
     pub fees_wallet: Addr,
     pub fees_percentage: u64,
+    pub fees_collected: Uint128,
     pub withdraw_wallet: Addr,
-    pub whitelist: Vec<WhitelistUser>,
+    pub withdrawn: Uint128,
+    pub soft_cap: Option<Uint128>,
+    pub total_raised: Uint128,
+    pub vesting: Option<VestingConfig>,
+    pub tge_time: Option<u64>,
+    pub whitelist_merkle_root: Option<String>,
+    pub signer_pubkey: Option<Binary>,
+    pub status: SaleStatus,
+    pub pause_reason: Option<String>,
+    /// Token sale supply not yet assigned to a phase, in whole tokens
+    pub unallocated_supply: Uint128,
+    pub escrow: EscrowResponse,
+}
+
+#[cw_serde]
+pub struct EscrowResponse {
+    /// Sale tokens funded so far, in base units
+    pub escrowed: Uint128,
+    /// Escrow needed before claims open, None while the token decimals are unknown
+    pub required: Option<Uint128>,
+    pub funded: bool,
+}
+
+#[cw_serde]
+pub struct StatusResponse {
+    pub status: SaleStatus,
+    pub pause_reason: Option<String>,
 }
 
 #[cw_serde]
@@ -101,9 +255,89 @@ pub struct PhaseInfoResponse {
     pub end_time: u64,
     pub price_per_token: Uint128,
     pub supply: u64,
-    pub address_list: Vec<Addr>,
+    pub address_list: u64,
     pub limit: u64,
     pub total_minted: u64,
+    pub total_raised: Uint128,
+    pub merkle_root: Option<String>,
+    pub signature_required: bool,
+    pub mode: PhaseMode,
+    pub total_committed: u64,
+    pub clearing_price: Option<Uint128>,
+    pub pause_reason: Option<String>,
+    pub rollover: RolloverPolicy,
+}
+
+#[cw_serde]
+pub struct PriceQuoteResponse {
+    /// Price of the next unit
+    pub price_per_token: Uint128,
+    /// Price of the quoted amount, priced piecewise across tiers or along the curve
+    pub total_price: Uint128,
+}
+
+#[cw_serde]
+pub struct WhitelistResponse {
+    pub whitelist: Vec<WhitelistUser>,
+}
+
+#[cw_serde]
+pub struct AllowlistResponse {
+    pub addresses: Vec<Addr>,
+}
+
+#[cw_serde]
+pub struct PendingAdminResponse {
+    pub pending_admin: Option<PendingAdmin>,
+}
+
+#[cw_serde]
+pub struct RolesResponse {
+    pub holders: Vec<RoleHolder>,
+}
+
+#[cw_serde]
+pub struct SimulateDepositResponse {
+    /// Active phase, if any
+    pub phase: Option<String>,
+    pub required_funds: Coin,
+    /// Sale tokens bought, an upper bound in pro-rata phases
+    pub tokens: Uint128,
+    pub remaining_supply: u64,
+    /// Units the address can still buy in the phase under its limit
+    pub remaining_phase_allowance: u64,
+    /// Whitelisted amount lifting the phase limit for a single deposit
+    pub whitelist_allowance: u64,
+    /// Why the deposit would be rejected, None when it would go through
+    pub rejection: Option<DepositRejection>,
+    pub error: Option<String>,
+}
+
+#[cw_serde]
+pub enum DepositRejection {
+    SaleNotStarted,
+    SaleEnded,
+    NoActivePhase,
+    NotInPhase,
+    AuthorizationRequired,
+    InvalidSignature,
+    AuthorizationExpired,
+    NonceUsed,
+    InvalidMerkleProof,
+    Overbuy,
+    ExceedsLimit,
+    PhaseLimitReached,
+    Paused,
+    InvalidStatus,
+    Underfunded,
+    Other,
+}
+
+#[cw_serde]
+pub struct FeesResponse {
+    pub fees_wallet: Addr,
+    pub fees_percentage: u64,
+    pub fees_collected: Uint128,
 }
 
 // Define the query message enum
@@ -111,9 +345,48 @@ pub struct PhaseInfoResponse {
 pub enum QueryMsg {
     GetLaunchpad {},
     GetUser {
-        address: Addr,
+        address: String,
+    },
+    AllUsers {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    TopBuyers {
+        start_after: Option<String>,
+        limit: Option<u32>,
     },
     GetPhase {
         phase_name: String,
     },
+    GetFees {},
+    Status {},
+    Whitelist {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    PhaseAllowlist {
+        phase_name: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Price of `amount` tokens in the phase at the current block time
+    PriceQuote {
+        phase_name: String,
+        amount: u64,
+    },
+    /// Runs the deposit checks for `address` without paying, proofs as in `Deposit`
+    SimulateDeposit {
+        address: String,
+        amount: u64,
+        phase_proof: Option<AllocationProof>,
+        whitelist_proof: Option<AllocationProof>,
+        authorization: Option<PurchaseAuthorization>,
+    },
+    /// Addresses holding delegated roles, the admin holds every role implicitly
+    Roles {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    PendingAdmin {},
+    ContractVersion {},
 }
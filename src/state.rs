@@ -1,8 +1,8 @@
-use std::collections::HashMap;
+use std::{ collections::HashMap, fmt };
 
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{ Addr, Uint128 };
-use cw_storage_plus::{ Item, Map };
+use cosmwasm_std::{ Addr, Binary, Uint128 };
+use cw_storage_plus::{ Index, IndexList, IndexedMap, Item, Map, MultiIndex };
 
 #[cw_serde]
 pub struct State {
@@ -16,9 +16,124 @@ pub struct State {
     pub phases: Vec<Phase>,
     pub claimable: bool,
     pub fees_wallet: Addr,
+    /// Platform fee taken from withdrawn funds, in basis points
     pub fees_percentage: u64,
+    pub fees_collected: Uint128,
     pub withdraw_wallet: Addr,
-    pub whitelist: Vec<WhitelistUser>,
+    /// Raised funds already sent out, fees included
+    pub withdrawn: Uint128,
+    /// Sale tokens already sent to buyers, in base units
+    pub total_claimed: Uint128,
+    /// Sale tokens funded through `FundSale`, in base units
+    pub escrowed: Uint128,
+    pub soft_cap: Option<Uint128>,
+    pub vesting: Option<VestingConfig>,
+    pub tge_time: Option<u64>,
+    /// Hex sha256 merkle root of `(address, amount)` whitelist allocations
+    pub whitelist_merkle_root: Option<String>,
+    /// Secp256k1 public key of the backend authorizing purchases in signature gated phases
+    pub signer_pubkey: Option<Binary>,
+    /// Lifecycle step reached through the admin, see `State::status`
+    pub stage: SaleStage,
+    /// Reason deposits and claims are halted, None while the sale runs
+    pub pause_reason: Option<String>,
+}
+
+// Lifecycle steps taken by the admin, the rest of the status follows the clock
+#[cw_serde]
+pub enum SaleStage {
+    Open,
+    Finalized,
+    Cancelled,
+}
+
+#[cw_serde]
+pub enum SaleStatus {
+    /// No phase configured yet
+    Draft,
+    Upcoming,
+    Active {
+        phase: Option<String>,
+    },
+    /// Active sale halted by the admin
+    Paused,
+    /// Sale over with the soft cap reached, waiting to be finalized
+    Ended,
+    /// Raised funds released to the project
+    Finalized,
+    Claimable,
+    Cancelled,
+    /// Sale over under the soft cap, buyers can claim refunds
+    Refunding,
+}
+
+impl fmt::Display for SaleStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let status = match self {
+            SaleStatus::Draft => "draft",
+            SaleStatus::Upcoming => "upcoming",
+            SaleStatus::Active { .. } => "active",
+            SaleStatus::Paused => "paused",
+            SaleStatus::Ended => "ended",
+            SaleStatus::Finalized => "finalized",
+            SaleStatus::Claimable => "claimable",
+            SaleStatus::Cancelled => "cancelled",
+            SaleStatus::Refunding => "refunding",
+        };
+        write!(f, "{}", status)
+    }
+}
+
+// Privileges delegated by the admin, owners hold every role
+#[cw_serde]
+pub enum Role {
+    Owner,
+    /// Maintains the whitelist and the phase allowlists
+    WhitelistManager,
+    /// Adds, updates and removes phases
+    PhaseOperator,
+    /// Withdraws the raised funds and the unsold tokens
+    Treasurer,
+    Pauser,
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let role = match self {
+            Role::Owner => "owner",
+            Role::WhitelistManager => "whitelist_manager",
+            Role::PhaseOperator => "phase_operator",
+            Role::Treasurer => "treasurer",
+            Role::Pauser => "pauser",
+        };
+        write!(f, "{}", role)
+    }
+}
+
+#[cw_serde]
+pub struct RoleHolder {
+    pub address: Addr,
+    pub roles: Vec<Role>,
+}
+
+// Admin transfer waiting for the new admin to accept it
+#[cw_serde]
+pub struct PendingAdmin {
+    pub address: Addr,
+    /// Time after which the proposal can no longer be accepted
+    pub expiry: Option<u64>,
+}
+
+#[cw_serde]
+pub struct VestingConfig {
+    /// Share of the allocation released at TGE, in basis points
+    pub tge_unlock_percentage: u64,
+    /// Seconds after TGE before the linear release starts
+    pub cliff: u64,
+    /// Seconds over which the remaining allocation is released
+    pub duration: u64,
+    /// Optional release granularity in seconds, e.g. 86400 for daily unlocks
+    pub period: Option<u64>,
 }
 
 #[cw_serde]
@@ -29,13 +144,40 @@ pub struct TokenInfo {
     pub decimals: Option<u8>,
     pub description: String,
     pub supply: u64,
+    #[serde(default)]
+    pub kind: SaleTokenKind,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub enum SaleTokenKind {
+    /// Cw20 contract at `denom`, funded before claims open
+    #[default]
+    Cw20,
+    /// Bank denom held by the contract, funded before claims open
+    Native,
+    /// Cw20 at `denom` minted by the launchpad on claim, instantiated from the cw20-base
+    /// `code_id` when the sale is finalized without a denom
+    Cw20Minter {
+        code_id: Option<u64>,
+    },
+    /// `factory/<contract>/<symbol>` created when the sale is finalized, claims mint it
+    TokenFactory,
 }
 #[cw_serde]
 pub struct User {
     pub buy_phases: HashMap<String, u64>,
     pub amount: u64,
     pub tokens: Uint128,
-    pub claimed: bool,
+    pub claimed_amount: Uint128,
+    pub paid: Uint128,
+    pub refunded: bool,
+    /// Pro-rata commitments per phase, waiting to be settled
+    #[serde(default)]
+    pub committed: HashMap<String, u64>,
+    /// Funds paid per dutch auction phase, waiting to be settled at the clearing price
+    #[serde(default)]
+    pub bids: HashMap<String, Uint128>,
 }
 
 #[cw_serde]
@@ -51,9 +193,95 @@ pub struct Phase {
     pub end_time: u64,
     pub price_per_token: Uint128,
     pub supply: u64,
-    pub address_list: Vec<Addr>,
     pub limit: u64,
     pub total_minted: u64,
+    #[serde(default)]
+    pub total_raised: Uint128,
+    /// Hex sha256 merkle root of `(address, limit)` allocations admitted to the phase
+    #[serde(default)]
+    pub merkle_root: Option<String>,
+    /// Deposits need a purchase authorization signed by the backend signer
+    #[serde(default)]
+    pub signature_required: bool,
+    #[serde(default)]
+    pub mode: PhaseMode,
+    /// Amount committed in a pro-rata phase, may exceed the supply
+    #[serde(default)]
+    pub total_committed: u64,
+    /// Dutch auction price at which the supply sold out
+    #[serde(default)]
+    pub clearing_price: Option<Uint128>,
+    /// Reason deposits in the phase and claims of its buyers are halted
+    #[serde(default)]
+    pub pause_reason: Option<String>,
+    /// What happens to the unsold supply once the phase ended
+    #[serde(default)]
+    pub rollover: RolloverPolicy,
+    /// Set once the rollover of the ended phase was applied
+    #[serde(default)]
+    pub rolled_over: bool,
+    /// Phases whose allowlist was carried over, their addresses are admitted as well
+    #[serde(default)]
+    pub inherited_allowlists: Vec<String>,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub enum RolloverPolicy {
+    /// Unsold supply stays in the ended phase
+    #[default]
+    None,
+    /// Unsold supply moves to the next phase
+    CarrySupply,
+    /// Unsold supply moves to the next phase, which also admits the phase allowlist
+    CarrySupplyAndAllowlist,
+    /// Unsold supply leaves the sale, escrowed tokens are burned
+    Burn,
+    /// Unsold supply leaves the sale, escrowed tokens are sent to the withdraw wallet
+    ReturnToProject,
+}
+
+impl fmt::Display for RolloverPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let policy = match self {
+            RolloverPolicy::None => "none",
+            RolloverPolicy::CarrySupply => "carry_supply",
+            RolloverPolicy::CarrySupplyAndAllowlist => "carry_supply_and_allowlist",
+            RolloverPolicy::Burn => "burn",
+            RolloverPolicy::ReturnToProject => "return_to_project",
+        };
+        write!(f, "{}", policy)
+    }
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub enum PhaseMode {
+    /// First come first served until the supply is sold out
+    #[default]
+    FixedPrice,
+    /// Commitments are scaled down to the supply once the phase ended
+    ProRata,
+    /// Price decays from `price_per_token` to `floor_price` over the phase window, by
+    /// `step` seconds increments when set, every buyer settles at the clearing price
+    DutchAuction {
+        floor_price: Uint128,
+        step: Option<u64>,
+    },
+    /// Consecutive price tiers over the phase sales, units past the last tier keep its price
+    Tiered {
+        tiers: Vec<PriceTier>,
+    },
+    /// The nth unit sold in the phase costs `price_per_token + slope * n`
+    BondingCurve {
+        slope: Uint128,
+    },
+}
+
+#[cw_serde]
+pub struct PriceTier {
+    pub supply: u64,
+    pub price: Uint128,
 }
 
 #[cw_serde]
@@ -66,12 +294,21 @@ pub struct PhaseResp {
     pub address_list: u64,
     pub limit: u64,
     pub total_minted: u64,
+    pub total_raised: Uint128,
+    pub merkle_root: Option<String>,
+    pub signature_required: bool,
+    pub mode: PhaseMode,
+    pub total_committed: u64,
+    pub clearing_price: Option<Uint128>,
+    pub pause_reason: Option<String>,
+    pub rollover: RolloverPolicy,
 }
 
 #[cw_serde]
 pub struct PhaseInformation {
     pub limit: u64,
     pub current_mint: u64,
+    pub committed: u64,
     pub eligible: bool,
 }
 
@@ -83,5 +320,32 @@ pub struct UserToken {
 
 pub const STATE_KEY: &str = "state";
 pub const STATE: Item<State> = Item::new(STATE_KEY);
+pub const PENDING_ADMIN: Item<PendingAdmin> = Item::new("pending_admin");
 pub const USER_INFO: Map<Addr, User> = Map::new("users");
-pub const USERS: Map<String, Vec<UserToken>> = Map::new("user_tokens");
+pub const WHITELIST: Map<Addr, u64> = Map::new("whitelist");
+// Keyed by phase name and generation, removing a phase bumps its generation instead of
+// deleting an unbounded number of entries
+pub const PHASE_ALLOWLIST: Map<(String, u64, Addr), ()> = Map::new("phase_allowlist");
+pub const PHASE_ALLOWLIST_SIZE: Map<String, u64> = Map::new("phase_allowlist_size");
+pub const PHASE_ALLOWLIST_GENERATION: Map<String, u64> = Map::new("phase_allowlist_generation");
+pub const USED_NONCES: Map<u64, ()> = Map::new("used_nonces");
+pub const ROLES: Map<Addr, Vec<Role>> = Map::new("roles");
+
+pub struct UserTokenIndexes<'a> {
+    pub tokens: MultiIndex<'a, u128, UserToken, Addr>,
+}
+
+impl<'a> IndexList<UserToken> for UserTokenIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<UserToken>> + '_> {
+        let v: Vec<&dyn Index<UserToken>> = vec![&self.tokens];
+        Box::new(v.into_iter())
+    }
+}
+
+// Tokens bought per address, indexed by amount for sorted queries
+pub fn users<'a>() -> IndexedMap<'a, Addr, UserToken, UserTokenIndexes<'a>> {
+    let indexes = UserTokenIndexes {
+        tokens: MultiIndex::new(|user| user.tokens.u128(), "user_token", "user_token__tokens"),
+    };
+    IndexedMap::new("user_token", indexes)
+}
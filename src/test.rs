@@ -1,18 +1,237 @@
 #[cfg(test)]
 mod tests {
-    use crate::contract::{ execute, instantiate };
-    use crate::msg::{ AllUsersResponse, ExecuteMsg, InstantiateMsg, UserInfoResponse };
-    use crate::state::{ Phase, TokenInfo, WhitelistUser, STATE, USER_INFO };
-    use std::collections::HashMap;
+    use crate::contract::{ execute, instantiate, migrate, query, reply };
+    use crate::error::ContractError;
+    use crate::injective::{
+        InjectiveMsg,
+        InjectiveMsgWrapper,
+        InjectiveQuery,
+        InjectiveQueryWrapper,
+        InjectiveRoute,
+        TokenFactoryCreateDenomFeeResponse,
+    };
+    use crate::merkle::{ leaf_hash, MerkleTree };
+    use crate::msg::{
+        AllocationProof,
+        AllUsersResponse,
+        AuthorizationPayload,
+        AllowlistResponse,
+        DepositRejection,
+        EscrowResponse,
+        ExecuteMsg,
+        FeesResponse,
+        InstantiateMsg,
+        LaunchpadResponse,
+        MigrateMsg,
+        PendingAdminResponse,
+        PhaseInfoResponse,
+        PriceQuoteResponse,
+        PurchaseAuthorization,
+        QueryMsg,
+        ReceiveMsg,
+        RolesResponse,
+        SimulateDepositResponse,
+        StatusResponse,
+        UserTokensResponse,
+    };
+    use crate::state::{
+        users,
+        Phase,
+        PhaseMode,
+        PriceTier,
+        Role,
+        RolloverPolicy,
+        SaleStatus,
+        SaleTokenKind,
+        TokenInfo,
+        VestingConfig,
+        WhitelistUser,
+        STATE,
+        USER_INFO,
+        WHITELIST,
+    };
+    use std::marker::PhantomData;
     use std::thread;
     use std::time::Duration;
 
-    use cosmwasm_std::testing::{ mock_dependencies_with_balance, mock_env, mock_info };
-    use cosmwasm_std::{ coins, Addr, Deps, DepsMut, StdResult, Uint128 };
+    use cosmwasm_std::testing::{
+        mock_env,
+        mock_info,
+        MockApi,
+        MockQuerier,
+        MockStorage,
+        MOCK_CONTRACT_ADDR,
+    };
+    use cosmwasm_std::{
+        coin,
+        coins,
+        from_json,
+        to_json_binary,
+        to_json_vec,
+        Addr,
+        Binary,
+        BankMsg,
+        Coin,
+        ContractResult,
+        CosmosMsg,
+        Env,
+        Event,
+        OwnedDeps,
+        Reply,
+        ReplyOn,
+        Response,
+        SubMsgResponse,
+        SubMsgResult,
+        SystemResult,
+        Uint128,
+        WasmMsg,
+        WasmQuery,
+    };
+    use cw2::{ set_contract_version, ContractVersion };
+    use cw20::{ BalanceResponse, Cw20ExecuteMsg, Cw20ReceiveMsg };
+    use cw_storage_plus::{ Item, Map };
+    use k256::ecdsa::{ signature::Signer, Signature, SigningKey };
+    use serde_json::{ json, Value };
+
+    const PRICE: u128 = 1000000000000000000;
+    const DENOM_CREATION_FEE: u128 = 10 * PRICE;
+
+    type Deps<'a> = cosmwasm_std::Deps<'a, InjectiveQueryWrapper>;
+    type DepsMut<'a> = cosmwasm_std::DepsMut<'a, InjectiveQueryWrapper>;
+    type MockDeps =
+        OwnedDeps<MockStorage, MockApi, MockQuerier<InjectiveQueryWrapper>, InjectiveQueryWrapper>;
+
+    // Mocked injective bindings holding `balance` for the contract
+    fn mock_dependencies_with_balance(balance: &[Coin]) -> MockDeps {
+        let querier = MockQuerier::<InjectiveQueryWrapper>::new(&[(MOCK_CONTRACT_ADDR, balance)]);
+        let querier = querier.with_custom_handler(|query| {
+            match query.query_data {
+                InjectiveQuery::TokenFactoryDenomCreationFee {} => {
+                    let fee = TokenFactoryCreateDenomFeeResponse {
+                        fee: coins(DENOM_CREATION_FEE, "inj"),
+                    };
+                    SystemResult::Ok(ContractResult::Ok(to_json_binary(&fee).unwrap()))
+                }
+            }
+        });
+        OwnedDeps {
+            storage: MockStorage::default(),
+            api: MockApi::default(),
+            querier,
+            custom_query_type: PhantomData,
+        }
+    }
+
+    fn public_phase(name: &str, start_time: u64, end_time: u64, supply: u64) -> Phase {
+        Phase {
+            name: name.to_string(),
+            start_time,
+            end_time,
+            price_per_token: Uint128::new(PRICE),
+            supply,
+            limit: 100,
+            total_minted: 0,
+            total_raised: Uint128::zero(),
+            merkle_root: None,
+            signature_required: false,
+            mode: PhaseMode::FixedPrice,
+            total_committed: 0,
+            clearing_price: None,
+            pause_reason: None,
+            rollover: RolloverPolicy::None,
+            rolled_over: false,
+            inherited_allowlists: vec![],
+        }
+    }
+
+    fn instantiate_launchpad(deps: DepsMut, phases: Vec<Phase>, soft_cap: Option<Uint128>) {
+        let instantiate_msg = InstantiateMsg {
+            admin: "admin".to_string(),
+            start_time: mock_env().block.time.seconds(),
+            end_time: mock_env().block.time.seconds() + 600,
+            phases: Some(phases),
+            buy_denom: "inj".to_string(),
+            buy_token_type: "native".into(),
+            tokens_per_buy: Uint128::new(1),
+            token_info: TokenInfo {
+                name: "TEST".to_string(),
+                symbol: "TEST".to_string(),
+                description: "TEST".to_string(),
+                denom: None,
+                decimals: None,
+                supply: 5000,
+                kind: SaleTokenKind::Cw20,
+            },
+            fees_wallet: "fees_wallet".to_string(),
+            fees_percentage: Some(250),
+            withdraw_wallet: "withdraw_wallet".to_string(),
+            whitelist: None,
+            soft_cap,
+            vesting: None,
+            whitelist_merkle_root: None,
+            signer_pubkey: None,
+        };
+        instantiate(deps, mock_env(), mock_info("creator", &[]), instantiate_msg).unwrap();
+    }
+
+    fn add_to_phase_allowlist(deps: DepsMut, phase_name: &str, addresses: &[&str]) {
+        let msg = ExecuteMsg::AddToPhaseAllowlist {
+            phase_name: phase_name.to_string(),
+            addresses: addresses.iter().map(|address| address.to_string()).collect(),
+        };
+        execute(deps, env_before_start(), mock_info("admin", &[]), msg).unwrap();
+    }
+
+    fn deposit_msg(amount: u64) -> ExecuteMsg {
+        ExecuteMsg::Deposit {
+            amount,
+            phase_proof: None,
+            whitelist_proof: None,
+            authorization: None,
+        }
+    }
+
+    fn env_at(seconds: u64) -> Env {
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(seconds);
+        env
+    }
+
+    // Phases and allowlists are only editable before the sale starts
+    fn env_before_start() -> Env {
+        let mut env = mock_env();
+        env.block.time = env.block.time.minus_seconds(1);
+        env
+    }
+
+    fn mock_sale_token_balance(
+        deps: &mut MockDeps,
+        balance: u128
+    ) {
+        deps.querier.update_wasm(move |query| {
+            match query {
+                WasmQuery::Smart { contract_addr, .. } if contract_addr == "sale_token" => {
+                    let balance = BalanceResponse { balance: Uint128::new(balance) };
+                    SystemResult::Ok(ContractResult::Ok(to_json_binary(&balance).unwrap()))
+                }
+                _ => panic!("unexpected query"),
+            }
+        });
+    }
+
+    // Escrows cw20 sale tokens sent by the project from the `sale_token` contract
+    fn fund_sale(deps: DepsMut, amount: u128) -> Result<Response<InjectiveMsgWrapper>, ContractError> {
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "project".to_string(),
+            amount: Uint128::new(amount),
+            msg: to_json_binary(&(ReceiveMsg::FundSale {})).unwrap(),
+        });
+        execute(deps, mock_env(), mock_info("sale_token", &[]), msg)
+    }
 
     // Test deposit functionality
     fn test_deposit(deps: DepsMut, address: String, amount: u64) {
-        let deposit_msg = ExecuteMsg::Deposit { amount };
+        let deposit_msg = deposit_msg(amount);
         let env = mock_env();
         let info = mock_info(&address, &coins((amount as u128) * 1000000000000000000, "inj")); // Adjust coin amount and denom as needed
 
@@ -43,13 +262,13 @@ mod tests {
         }
     }
 
-    fn flip_claim_status(deps: DepsMut, address: String) {
+    fn flip_claim_status(deps: DepsMut, address: String) -> Result<Response<InjectiveMsgWrapper>, ContractError> {
         let deposit_msg = ExecuteMsg::FlipClaimStatus {};
         let env = mock_env();
         let info = mock_info(&address, &[]); // Adjust coin amount and denom as needed
 
         let res = execute(deps, env, info, deposit_msg);
-        match res {
+        match &res {
             Ok(response) => {
                 // Assert successful response attributes, messages, or data here.
                 // For example:
@@ -64,15 +283,16 @@ mod tests {
                 println!("Error: {:?}", e);
             }
         }
+        res
     }
 
-    fn claim_tokens(deps: DepsMut, address: String) {
+    fn claim_tokens(deps: DepsMut, address: String) -> Result<Response<InjectiveMsgWrapper>, ContractError> {
         let claim_msg = ExecuteMsg::ClaimTokens {};
         let env = mock_env();
         let info = mock_info(&address, &[]); // Adjust coin amount and denom as needed
 
         let res = execute(deps, env, info, claim_msg);
-        match res {
+        match &res {
             Ok(response) => {
                 // Assert successful response attributes, messages, or data here.
                 // For example:
@@ -89,55 +309,69 @@ mod tests {
                 println!("Error: {:?}", e);
             }
         }
+        res
     }
 
-    fn update_state(deps: DepsMut, address: String, denom: String) {
-        let state = STATE.load(deps.storage);
-        match state {
-            Ok(st) => {
-                let update_token_denom = ExecuteMsg::UpdateConfig {
-                    start_time: None,
-                    end_time: None,
-                    buy_denom: None,
-                    buy_token_type: None,
-                    tokens_per_buy: None,
-                    token_info: Some(TokenInfo {
-                        name: st.token_info.name,
-                        symbol: st.token_info.symbol,
-                        denom: Some(denom.clone()),
-                        description: st.token_info.description,
-                        supply: 5000,
-                    }),
-                    phases: None,
-                    new_admin: None,
-                };
-                let env = mock_env();
-                let info = mock_info(&address, &[]); // Adjust coin amount and denom as needed
-
-                let res = execute(deps, env, info, update_token_denom);
-                match res {
-                    Ok(response) => {
-                        // Assert successful response attributes, messages, or data here.
-                        // For example:
-                        println!("Successfully update token denom to {}", denom);
-                        // You might want to check the user's token balance or the total minted amount in the phase.
-                    }
-                    Err(e) => {
-                        // Handle error, this could be a limit exceeded, phase timing issue, etc.
-                        println!("Error: {:?}", e);
-                    }
-
-                    // Further assertions can be made here to ensure tokens are correctly allocated, etc.
-                }
+    // Switches the sale token to one issued by the launchpad, before the sale starts
+    fn set_sale_token(deps: DepsMut, kind: SaleTokenKind) {
+        let token_info = TokenInfo {
+            name: "TEST".to_string(),
+            symbol: "TEST".to_string(),
+            description: "TEST".to_string(),
+            denom: None,
+            decimals: Some(6),
+            supply: 5000,
+            kind,
+        };
+        let msg = update_config_msg(json!({ "token_info": token_info }));
+        execute(deps, env_before_start(), mock_info("admin", &[]), msg).unwrap();
+    }
+
+    // UpdateConfig only setting the given fields, e.g. `json!({ "soft_cap": "100" })`
+    fn update_config_msg(fields: Value) -> ExecuteMsg {
+        serde_json::from_value(json!({ "update_config": fields })).unwrap()
+    }
+
+    fn update_state(
+        deps: DepsMut,
+        address: String,
+        denom: String
+    ) -> Result<Response<InjectiveMsgWrapper>, ContractError> {
+        let st = STATE.load(deps.storage)?;
+        let update_token_denom = update_config_msg(json!({
+            "token_info": TokenInfo {
+                name: st.token_info.name,
+                symbol: st.token_info.symbol,
+                denom: Some(denom.clone()),
+                decimals: Some(18),
+                description: st.token_info.description,
+                supply: 5000,
+                kind: SaleTokenKind::Cw20,
+            },
+        }));
+        let env = mock_env();
+        let info = mock_info(&address, &[]); // Adjust coin amount and denom as needed
+
+        let res = execute(deps, env, info, update_token_denom);
+        match &res {
+            Ok(_response) => {
+                // Assert successful response attributes, messages, or data here.
+                // For example:
+                println!("Successfully update token denom to {}", denom);
+                // You might want to check the user's token balance or the total minted amount in the phase.
+            }
+            Err(e) => {
+                // Handle error, this could be a limit exceeded, phase timing issue, etc.
+                println!("Error: {:?}", e);
             }
-            Err(e) => println!("Error: {:?}", e),
         }
+        res
     }
 
     fn add_whitelist(deps: DepsMut, address: String, whitelist: Vec<WhitelistUser>) {
         let deposit_msg = ExecuteMsg::AddWhitelist { whitelist };
 
-        let env = mock_env();
+        let env = env_before_start();
         let info = mock_info(&address, &[]); // Adjust coin amount and denom as needed
 
         let res = execute(deps, env, info, deposit_msg);
@@ -168,9 +402,18 @@ mod tests {
                 end_time: mock_env().block.time.seconds(),
                 price_per_token: Uint128::new(1000000000000000000),
                 supply: 150,
-                address_list: vec![Addr::unchecked("addr1"), Addr::unchecked("addr2")],
                 limit: 5,
                 total_minted: 0,
+                total_raised: Uint128::zero(),
+                merkle_root: None,
+                signature_required: false,
+                mode: PhaseMode::FixedPrice,
+                total_committed: 0,
+                clearing_price: None,
+                pause_reason: None,
+                rollover: RolloverPolicy::None,
+                rolled_over: false,
+                inherited_allowlists: vec![],
             },
             Phase {
                 name: "WL".to_string(),
@@ -178,9 +421,18 @@ mod tests {
                 end_time: mock_env().block.time.seconds() + 20,
                 price_per_token: Uint128::new(1000000000000000000),
                 supply: 10000,
-                address_list: vec![Addr::unchecked("addr3")],
                 limit: 1200,
                 total_minted: 0,
+                total_raised: Uint128::zero(),
+                merkle_root: None,
+                signature_required: false,
+                mode: PhaseMode::FixedPrice,
+                total_committed: 0,
+                clearing_price: None,
+                pause_reason: None,
+                rollover: RolloverPolicy::None,
+                rolled_over: false,
+                inherited_allowlists: vec![],
             },
             Phase {
                 name: "Public".to_string(),
@@ -188,15 +440,24 @@ mod tests {
                 end_time: mock_env().block.time.seconds() + 50,
                 price_per_token: Uint128::new(1000000000000000000),
                 supply: 20000,
-                address_list: vec![],
                 limit: 1200,
                 total_minted: 0,
+                total_raised: Uint128::zero(),
+                merkle_root: None,
+                signature_required: false,
+                mode: PhaseMode::FixedPrice,
+                total_committed: 0,
+                clearing_price: None,
+                pause_reason: None,
+                rollover: RolloverPolicy::None,
+                rolled_over: false,
+                inherited_allowlists: vec![],
             }
         ];
 
         // First, instantiate your contract
         let instantiate_msg = InstantiateMsg {
-            admin: Addr::unchecked("admin"),
+            admin: "admin".to_string(),
             start_time: mock_env().block.time.seconds(),
             end_time: mock_env().block.time.seconds() + 600,
             phases: Option::Some(phases), // You can define phases here if needed
@@ -208,34 +469,44 @@ mod tests {
                 symbol: "TEST".to_string(),
                 description: "TEST".to_string(),
                 denom: None,
-                supply: 5000,
+                decimals: None,
+                supply: 30150,
+                kind: SaleTokenKind::Cw20,
             },
-            fees_wallet: Addr::unchecked("fees_wallet"),
-            withdraw_wallet: Addr::unchecked("withdraw_wallet"),
+            fees_wallet: "fees_wallet".to_string(),
+            fees_percentage: Some(250),
+            withdraw_wallet: "withdraw_wallet".to_string(),
             whitelist: Some(Vec::new()),
+            soft_cap: None,
+            vesting: None,
+            whitelist_merkle_root: None,
+            signer_pubkey: None,
         };
         let instantiate_env = mock_env();
         let instantiate_info = mock_info("creator", &coins(100, "$note"));
 
         instantiate(deps.as_mut(), instantiate_env, instantiate_info, instantiate_msg).unwrap();
 
+        add_to_phase_allowlist(deps.as_mut(), "OG", &["addr1", "addr2"]);
+        add_to_phase_allowlist(deps.as_mut(), "WL", &["addr3"]);
+
         let whitelist = vec![WhitelistUser { address: Addr::unchecked("addr1"), amount: 100u64 }];
         add_whitelist(deps.as_mut(), "admin".to_string(), whitelist);
-        let state = STATE.load(deps.as_ref().storage).unwrap();
-        println!("WHITELIST: {:?}", state.whitelist);
+        let whitelist = WHITELIST.may_load(deps.as_ref().storage, Addr::unchecked("addr1")).unwrap();
+        println!("WHITELIST: {:?}", whitelist);
 
         //OG
         test_deposit(deps.as_mut(), "addr1".to_string(), 100);
         thread::sleep(Duration::from_secs(2));
         test_deposit(deps.as_mut(), "addr1".to_string(), 5);
         let state = STATE.load(deps.as_ref().storage).unwrap();
-        // // let serialized_phase = serde_json::to_string_pretty(&state.phases.get(0)).unwrap();
+        // // let serialized_phase = serde_json::to_string_pretty(&state.phases.first()).unwrap();
         println!(
             "Name: {}\tTotal Minted: {}\tStart: {}\tEnd: {}\n",
-            &state.phases.get(0).unwrap().name,
-            &state.phases.get(0).unwrap().total_minted,
-            &state.phases.get(0).unwrap().start_time,
-            &state.phases.get(0).unwrap().end_time
+            &state.phases.first().unwrap().name,
+            &state.phases.first().unwrap().total_minted,
+            &state.phases.first().unwrap().start_time,
+            &state.phases.first().unwrap().end_time
         );
 
         // //thread::sleep(Duration::from_secs(8));
@@ -271,9 +542,1498 @@ mod tests {
         // //let serialized_users = serde_json::to_string_pretty(&all_users_response.users).unwrap();
         // //println!("All Users: {}", serialized_users);
 
-        // update_state(deps.as_mut(), "admin".to_string(), "$note".to_string());
-        // flip_claim_status(deps.as_mut(), "admin".to_string());
+        // The sale is still running, it can't be settled nor claimed yet
+        let err = update_state(deps.as_mut(), "admin".to_string(), "$note".to_string()).unwrap_err();
+        assert_eq!(err, ContractError::SaleStarted {});
+        let err = flip_claim_status(deps.as_mut(), "admin".to_string()).unwrap_err();
+        assert_eq!(err, ContractError::InvalidStatus { status: "active".to_string() });
+
+        let err = claim_tokens(deps.as_mut(), "admin".to_string()).unwrap_err();
+        assert_eq!(err, ContractError::NotClaimable {});
+    }
+
+    #[test]
+    fn soft_cap_refund_test() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let start = mock_env().block.time.seconds();
+        instantiate_launchpad(
+            deps.as_mut(),
+            vec![public_phase("Public", start, start + 600, 1000)],
+            Some(Uint128::new(100 * PRICE))
+        );
+
+        let info = mock_info("addr1", &coins(5 * PRICE, "inj"));
+        execute(deps.as_mut(), mock_env(), info, deposit_msg(5)).unwrap();
+        let usr = USER_INFO.load(deps.as_ref().storage, Addr::unchecked("addr1")).unwrap();
+        assert_eq!(usr.paid, Uint128::new(5 * PRICE));
+
+        // Refunds are only available once the sale has ended under the soft cap
+        let refund = ExecuteMsg::ClaimRefund {};
+        let err = execute(deps.as_mut(), mock_env(), mock_info("addr1", &[]), refund.clone());
+        assert_eq!(err.unwrap_err(), ContractError::RefundNotAvailable {});
+
+        let ended = env_at(601);
+        let withdraw = ExecuteMsg::Withdraw {};
+        let err = execute(deps.as_mut(), ended.clone(), mock_info("admin", &[]), withdraw).unwrap_err();
+        assert_eq!(err, ContractError::SoftCapNotReached {});
+        let flip = ExecuteMsg::FlipClaimStatus {};
+        let err = execute(deps.as_mut(), ended.clone(), mock_info("admin", &[]), flip).unwrap_err();
+        assert_eq!(err, ContractError::SoftCapNotReached {});
+
+        let res = execute(deps.as_mut(), ended.clone(), mock_info("addr1", &[]), refund.clone()).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr1".to_string(),
+                amount: coins(5 * PRICE, "inj"),
+            })
+        );
+
+        // No double refunds, and nothing for addresses that never bought
+        let err = execute(deps.as_mut(), ended.clone(), mock_info("addr1", &[]), refund.clone()).unwrap_err();
+        assert_eq!(err, ContractError::AlreadyRefunded {});
+        let err = execute(deps.as_mut(), ended, mock_info("addr2", &[]), refund).unwrap_err();
+        assert_eq!(err, ContractError::UserNotFound {});
+    }
+
+    // Extracts the amount of the single CW20 transfer carried by a response
+    fn cw20_transfer_amount(res: &Response<InjectiveMsgWrapper>) -> Uint128 {
+        match &res.messages[0].msg {
+            CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) =>
+                match from_json(msg).unwrap() {
+                    Cw20ExecuteMsg::Transfer { amount, .. } => amount,
+                    other => panic!("unexpected cw20 message {:?}", other),
+                }
+            other => panic!("unexpected message {:?}", other),
+        }
+    }
+
+    #[test]
+    fn vesting_claim_test() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let start = mock_env().block.time.seconds();
+        instantiate_launchpad(deps.as_mut(), vec![public_phase("Public", start, start + 600, 1000)], None);
+
+        let info = mock_info("addr1", &coins(10 * PRICE, "inj"));
+        execute(deps.as_mut(), mock_env(), info, deposit_msg(10)).unwrap();
+
+        STATE.update(deps.as_mut().storage, |mut state| -> cosmwasm_std::StdResult<_> {
+            state.token_info.denom = Some("sale_token".to_string());
+            state.token_info.decimals = Some(6);
+            state.vesting = Some(VestingConfig {
+                tge_unlock_percentage: 2000,
+                cliff: 100,
+                duration: 1000,
+                period: Some(100),
+            });
+            Ok(state)
+        }).unwrap();
+        fund_sale(deps.as_mut(), 5000 * 1000000).unwrap();
+
+        let ended = env_at(700);
+        execute(deps.as_mut(), ended.clone(), mock_info("admin", &[]), ExecuteMsg::Finalize {}).unwrap();
+        let flip = ExecuteMsg::FlipClaimStatus {};
+        execute(deps.as_mut(), ended.clone(), mock_info("admin", &[]), flip).unwrap();
+
+        let claim = ExecuteMsg::ClaimTokens {};
+        let res = execute(deps.as_mut(), ended, mock_info("addr1", &[]), claim.clone()).unwrap();
+        assert_eq!(cw20_transfer_amount(&res), Uint128::new(2_000_000));
+
+        // Nothing more before the cliff ends
+        let err = execute(deps.as_mut(), env_at(750), mock_info("addr1", &[]), claim.clone());
+        assert_eq!(err.unwrap_err(), ContractError::NothingToClaim {});
+
+        // Half way through the linear release, rounded down to a whole period
+        let res = execute(deps.as_mut(), env_at(1350), mock_info("addr1", &[]), claim.clone()).unwrap();
+        assert_eq!(cw20_transfer_amount(&res), Uint128::new(4_000_000));
+
+        let res = execute(deps.as_mut(), env_at(2000), mock_info("addr1", &[]), claim.clone()).unwrap();
+        assert_eq!(cw20_transfer_amount(&res), Uint128::new(4_000_000));
+        let usr = USER_INFO.load(deps.as_ref().storage, Addr::unchecked("addr1")).unwrap();
+        assert_eq!(usr.claimed_amount, Uint128::new(10_000_000));
+
+        let err = execute(deps.as_mut(), env_at(3000), mock_info("addr1", &[]), claim).unwrap_err();
+        assert_eq!(err, ContractError::AlreadyClaimed {});
+
+        // Claims are priced from the token info, it is frozen once they opened
+        let mut token_info = STATE.load(deps.as_ref().storage).unwrap().token_info;
+        token_info.decimals = Some(18);
+        let msg = update_config_msg(json!({ "token_info": token_info }));
+        let err = execute(deps.as_mut(), env_at(3000), mock_info("admin", &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::TokenInfoLocked {});
+    }
+
+    #[test]
+    fn withdraw_fees_test() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let start = mock_env().block.time.seconds();
+        instantiate_launchpad(deps.as_mut(), vec![public_phase("Public", start, start + 600, 1000)], None);
+
+        let info = mock_info("addr1", &coins(40 * PRICE, "inj"));
+        execute(deps.as_mut(), mock_env(), info, deposit_msg(40)).unwrap();
+
+        let withdraw = ExecuteMsg::Withdraw {};
+        let err = execute(deps.as_mut(), env_at(601), mock_info("admin", &[]), withdraw.clone());
+        assert_eq!(err.unwrap_err(), ContractError::InvalidStatus { status: "ended".to_string() });
+
+        execute(deps.as_mut(), env_at(601), mock_info("admin", &[]), ExecuteMsg::Finalize {}).unwrap();
+        let res = execute(deps.as_mut(), env_at(601), mock_info("admin", &[]), withdraw.clone()).unwrap();
+        assert_eq!(
+            res.messages
+                .iter()
+                .map(|msg| msg.msg.clone())
+                .collect::<Vec<_>>(),
+            vec![
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "withdraw_wallet".to_string(),
+                    amount: coins(39 * PRICE, "inj"),
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "fees_wallet".to_string(),
+                    amount: coins(PRICE, "inj"),
+                })
+            ]
+        );
+
+        let fees: FeesResponse = from_json(
+            query(deps.as_ref(), mock_env(), QueryMsg::GetFees {}).unwrap()
+        ).unwrap();
+        assert_eq!(fees.fees_percentage, 250);
+        assert_eq!(fees.fees_collected, Uint128::new(PRICE));
+
+        // Raised funds can only be withdrawn once
+        let err = execute(deps.as_mut(), env_at(602), mock_info("admin", &[]), withdraw).unwrap_err();
+        assert_eq!(err, ContractError::NothingToWithdraw {});
+    }
+
+    #[test]
+    fn cw20_deposit_test() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let start = mock_env().block.time.seconds();
+        instantiate_launchpad(deps.as_mut(), vec![public_phase("Public", start, start + 600, 1000)], None);
+        let msg = update_config_msg(json!({ "buy_denom": "usdt_token", "buy_token_type": "cw20" }));
+        execute(deps.as_mut(), env_before_start(), mock_info("admin", &[]), msg).unwrap();
+
+        let receive = |amount: u128| {
+            ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: "addr1".to_string(),
+                amount: Uint128::new(amount),
+                msg: to_json_binary(&(ReceiveMsg::Deposit {
+                    amount: 3,
+                    phase_proof: None,
+                    whitelist_proof: None,
+                    authorization: None,
+                })).unwrap(),
+            })
+        };
+
+        // Only the configured cw20 can call the hook, and it must carry the exact price
+        let info = mock_info("fake_token", &[]);
+        let err = execute(deps.as_mut(), mock_env(), info, receive(3 * PRICE)).unwrap_err();
+        assert_eq!(err, ContractError::InvalidCw20Token {});
+        let info = mock_info("usdt_token", &[]);
+        let err = execute(deps.as_mut(), mock_env(), info, receive(2 * PRICE)).unwrap_err();
+        assert_eq!(err, ContractError::WrongPayment {
+            expected: Uint128::new(3 * PRICE),
+            got: Uint128::new(2 * PRICE),
+        });
+
+        // Native funds are rejected for a cw20 sale
+        let info = mock_info("addr1", &coins(3 * PRICE, "usdt_token"));
+        let err = execute(deps.as_mut(), mock_env(), info, deposit_msg(3)).unwrap_err();
+        assert_eq!(err, ContractError::WrongTokenType { expected: "cw20".to_string() });
+
+        let info = mock_info("usdt_token", &[]);
+        execute(deps.as_mut(), mock_env(), info, receive(3 * PRICE)).unwrap();
+        let usr = USER_INFO.load(deps.as_ref().storage, Addr::unchecked("addr1")).unwrap();
+        assert_eq!(usr.amount, 3);
+        assert_eq!(usr.paid, Uint128::new(3 * PRICE));
+    }
+
+    #[test]
+    fn withdraw_unsold_test() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let start = mock_env().block.time.seconds();
+        instantiate_launchpad(deps.as_mut(), vec![public_phase("Public", start, start + 600, 1000)], None);
+
+        let info = mock_info("addr1", &coins(10 * PRICE, "inj"));
+        execute(deps.as_mut(), mock_env(), info, deposit_msg(10)).unwrap();
+        STATE.update(deps.as_mut().storage, |mut state| -> cosmwasm_std::StdResult<_> {
+            state.token_info.denom = Some("sale_token".to_string());
+            state.token_info.decimals = Some(6);
+            Ok(state)
+        }).unwrap();
+        fund_sale(deps.as_mut(), 25_000_000).unwrap();
+        deps.querier.update_wasm(|query| {
+            match query {
+                WasmQuery::Smart { contract_addr, .. } if contract_addr == "sale_token" => {
+                    let balance = BalanceResponse { balance: Uint128::new(25_000_000) };
+                    SystemResult::Ok(ContractResult::Ok(to_json_binary(&balance).unwrap()))
+                }
+                _ => panic!("unexpected query"),
+            }
+        });
+
+        let withdraw_unsold = ExecuteMsg::WithdrawUnsold {};
+        let err = execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), withdraw_unsold.clone());
+        assert_eq!(err.unwrap_err(), ContractError::SaleNotEnded {});
+
+        // Only the tokens not owed to buyers go back to the project
+        let res = execute(deps.as_mut(), env_at(601), mock_info("admin", &[]), withdraw_unsold).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "sale_token".to_string(),
+                msg: to_json_binary(
+                    &(Cw20ExecuteMsg::Transfer {
+                        recipient: "withdraw_wallet".to_string(),
+                        amount: Uint128::new(15_000_000),
+                    })
+                ).unwrap(),
+                funds: vec![],
+            })
+        );
+        let state = STATE.load(deps.as_ref().storage).unwrap();
+        assert_eq!(state.escrowed, Uint128::new(10_000_000));
+        assert_eq!((state.phases[0].supply, state.token_info.supply), (10, 10));
+
+        // The buyers' tokens left in the contract still cover the claims
+        let ended = env_at(601);
+        execute(deps.as_mut(), ended.clone(), mock_info("admin", &[]), ExecuteMsg::Finalize {}).unwrap();
+        let flip = ExecuteMsg::FlipClaimStatus {};
+        execute(deps.as_mut(), ended.clone(), mock_info("admin", &[]), flip).unwrap();
+        let claim = ExecuteMsg::ClaimTokens {};
+        let res = execute(deps.as_mut(), ended, mock_info("addr1", &[]), claim).unwrap();
+        assert_eq!(cw20_transfer_amount(&res), Uint128::new(10_000_000));
+    }
+
+    #[test]
+    fn migrate_legacy_test() {
+        // Part of the raise was already withdrawn from the 1.0 sale
+        let mut deps = mock_dependencies_with_balance(&coins(15, "inj"));
+
+        // Raw 1.0 layout, written before contract versioning existed
+        let legacy_state: Item<Value> = Item::new("state");
+        let legacy_users: Map<Addr, Value> = Map::new("users");
+        legacy_state.save(deps.as_mut().storage, &json!({
+            "admin": "admin",
+            "token_info": {
+                "name": "TEST",
+                "symbol": "TEST",
+                "denom": "sale_token",
+                "decimals": 6,
+                "description": "TEST",
+                "supply": 5000
+            },
+            "buy_denom": "inj",
+            "buy_token_type": "native",
+            "tokens_per_buy": "1",
+            "start_time": 1,
+            "end_time": 2,
+            "phases": [{
+                "name": "Public",
+                "start_time": 1,
+                "end_time": 2,
+                "price_per_token": "10",
+                "supply": 100,
+                "address_list": ["addr1", "addr1"],
+                "limit": 10,
+                "total_minted": 4
+            }, {
+                "name": "Late",
+                "start_time": 2,
+                "end_time": 3,
+                "price_per_token": "10",
+                "supply": 146,
+                "address_list": [],
+                "limit": 10,
+                "total_minted": 2
+            }],
+            "claimable": false,
+            "fees_wallet": "fees_wallet",
+            "fees_percentage": 0,
+            "withdraw_wallet": "withdraw_wallet",
+            "whitelist": [{ "address": "addr1", "amount": 5 }]
+        })).unwrap();
+        legacy_users.save(deps.as_mut().storage, Addr::unchecked("addr1"), &json!({
+            "buy_phases": { "Public": 4 },
+            "amount": 4,
+            "tokens": "4",
+            "claimed": false
+        })).unwrap();
+        legacy_users.save(deps.as_mut().storage, Addr::unchecked("addr2"), &json!({
+            "buy_phases": { "Late": 2 },
+            "amount": 2,
+            "tokens": "2",
+            "claimed": false
+        })).unwrap();
+        let legacy_user_tokens: Map<String, Value> = Map::new("user_tokens");
+        legacy_user_tokens.save(deps.as_mut().storage, "user_tokens".to_string(), &json!([
+            { "address": "addr1", "tokens": "7" }
+        ])).unwrap();
+        // The project funded the 1.0 sale before the upgrade
+        mock_sale_token_balance(&mut deps, 5000 * 1000000);
+
+        migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+
+        let state = STATE.load(deps.as_ref().storage).unwrap();
+        assert_eq!(state.phases[0].total_raised, Uint128::new(40));
+        assert_eq!(state.withdrawn, Uint128::new(45));
+        // The Public leftover was carried into Late on its first purchase
+        for phase in state.phases.iter() {
+            assert_eq!(phase.rollover, RolloverPolicy::CarrySupplyAndAllowlist);
+        }
+        assert!(state.phases[0].rolled_over && !state.phases[1].rolled_over);
+        assert_eq!((state.phases[0].supply, state.phases[1].supply), (4, 146));
+        assert_eq!(state.escrowed, Uint128::new(5000 * 1000000));
+        let usr = USER_INFO.load(deps.as_ref().storage, Addr::unchecked("addr1")).unwrap();
+        assert_eq!(usr.paid, Uint128::new(40));
+        assert_eq!(usr.claimed_amount, Uint128::zero());
+        let user_tokens = users().load(deps.as_ref().storage, Addr::unchecked("addr1")).unwrap();
+        assert_eq!(user_tokens.tokens, Uint128::new(4));
+        assert!(!legacy_user_tokens.has(deps.as_ref().storage, "user_tokens".to_string()));
+        let whitelisted = WHITELIST.load(deps.as_ref().storage, Addr::unchecked("addr1")).unwrap();
+        assert_eq!(whitelisted, 5);
+        let allowlist: AllowlistResponse = from_json(
+            query(deps.as_ref(), mock_env(), QueryMsg::PhaseAllowlist {
+                phase_name: "Public".to_string(),
+                start_after: None,
+                limit: None,
+            }).unwrap()
+        ).unwrap();
+        assert_eq!(allowlist.addresses, vec![Addr::unchecked("addr1")]);
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetPhase { phase_name: "Public".to_string() });
+        assert_eq!(from_json::<PhaseInfoResponse>(&res.unwrap()).unwrap().address_list, 1);
+
+        let version: ContractVersion = from_json(
+            query(deps.as_ref(), mock_env(), QueryMsg::ContractVersion {}).unwrap()
+        ).unwrap();
+        assert_eq!(version.version, env!("CARGO_PKG_VERSION"));
+
+        // Migrating again is a no-op, going back to an older code is refused
+        migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+        set_contract_version(deps.as_mut().storage, "Discoverinj-Launchpad", "99.0.0").unwrap();
+        let err = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap_err();
+        assert_eq!(err, ContractError::CannotDowngrade {
+            from: "99.0.0".to_string(),
+            to: env!("CARGO_PKG_VERSION").to_string(),
+        });
+    }
+
+    #[test]
+    fn all_users_pagination_test() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let start = mock_env().block.time.seconds();
+        instantiate_launchpad(deps.as_mut(), vec![public_phase("Public", start, start + 600, 1000)], None);
+
+        for (address, amount) in [("addr1", 1u64), ("addr2", 2), ("addr3", 3)] {
+            let info = mock_info(address, &coins((amount as u128) * PRICE, "inj"));
+            execute(deps.as_mut(), mock_env(), info, deposit_msg(amount)).unwrap();
+        }
+
+        let page: AllUsersResponse = from_json(
+            query(deps.as_ref(), mock_env(), QueryMsg::AllUsers { start_after: None, limit: Some(2) }).unwrap()
+        ).unwrap();
+        assert_eq!(
+            page.users
+                .iter()
+                .map(|(address, _)| address.as_str())
+                .collect::<Vec<_>>(),
+            vec!["addr1", "addr2"]
+        );
+
+        let start_after = Some("addr2".to_string());
+        let page: AllUsersResponse = from_json(
+            query(deps.as_ref(), mock_env(), QueryMsg::AllUsers { start_after, limit: None }).unwrap()
+        ).unwrap();
+        assert_eq!(page.users.len(), 1);
+        let (address, info) = &page.users[0];
+        assert_eq!(address, "addr3");
+        assert_eq!(info.tokens, Uint128::new(3));
+        assert_eq!(info.phases["Public"].current_mint, 3);
+        assert_eq!(info.claimed_amount, Uint128::zero());
+
+        // Cursors are validated like any other address
+        let start_after = Some("ADDR2".to_string());
+        query(deps.as_ref(), mock_env(), QueryMsg::AllUsers { start_after, limit: None }).unwrap_err();
+        let msg = QueryMsg::GetUser { address: "ADDR1".to_string() };
+        query(deps.as_ref(), mock_env(), msg).unwrap_err();
+    }
+
+    #[test]
+    fn top_buyers_test() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let start = mock_env().block.time.seconds();
+        instantiate_launchpad(deps.as_mut(), vec![public_phase("Public", start, start + 600, 1000)], None);
+
+        for (address, amount) in [("addr1", 2u64), ("addr2", 5), ("addr1", 4), ("addr3", 1)] {
+            let info = mock_info(address, &coins((amount as u128) * PRICE, "inj"));
+            execute(deps.as_mut(), mock_env(), info, deposit_msg(amount)).unwrap();
+        }
+
+        // Repeated purchases add up once
+        let user_tokens = users().load(deps.as_ref().storage, Addr::unchecked("addr1")).unwrap();
+        assert_eq!(user_tokens.tokens, Uint128::new(6));
+
+        let top_buyers = |start_after: Option<&str>| -> Vec<(String, u128)> {
+            let res: UserTokensResponse = from_json(
+                query(deps.as_ref(), mock_env(), QueryMsg::TopBuyers {
+                    start_after: start_after.map(str::to_string),
+                    limit: Some(2),
+                }).unwrap()
+            ).unwrap();
+            res.users
+                .into_iter()
+                .map(|user| (user.address.to_string(), user.tokens.u128()))
+                .collect()
+        };
+        assert_eq!(top_buyers(None), vec![("addr1".to_string(), 6), ("addr2".to_string(), 5)]);
+        assert_eq!(top_buyers(Some("addr2")), vec![("addr3".to_string(), 1)]);
+    }
+
+    #[test]
+    fn phase_allowlist_test() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let start = mock_env().block.time.seconds();
+        instantiate_launchpad(
+            deps.as_mut(),
+            vec![
+                public_phase("OG", start, start + 100, 1000),
+                public_phase("WL", start + 101, start + 200, 1000)
+            ],
+            None
+        );
+        add_to_phase_allowlist(deps.as_mut(), "OG", &["addr1", "addr2", "addr3"]);
+        add_to_phase_allowlist(deps.as_mut(), "WL", &["addr4"]);
+
+        let msg = ExecuteMsg::RemoveFromPhaseAllowlist {
+            phase_name: "OG".to_string(),
+            addresses: vec!["addr2".to_string()],
+        };
+        execute(deps.as_mut(), env_before_start(), mock_info("admin", &[]), msg).unwrap();
+
+        let allowlist: AllowlistResponse = from_json(
+            query(deps.as_ref(), mock_env(), QueryMsg::PhaseAllowlist {
+                phase_name: "OG".to_string(),
+                start_after: Some("addr1".to_string()),
+                limit: None,
+            }).unwrap()
+        ).unwrap();
+        assert_eq!(allowlist.addresses, vec![Addr::unchecked("addr3")]);
+
+        // A phase re-added under a removed name starts with an empty allowlist
+        let late = || public_phase("Late", start + 201, start + 300, 1000);
+        let msg = ExecuteMsg::AddPhase { phase: late() };
+        execute(deps.as_mut(), env_before_start(), mock_info("admin", &[]), msg).unwrap();
+        add_to_phase_allowlist(deps.as_mut(), "Late", &["addr5"]);
+        let msg = ExecuteMsg::RemovePhase { name: "Late".to_string() };
+        execute(deps.as_mut(), env_before_start(), mock_info("admin", &[]), msg).unwrap();
+        let msg = ExecuteMsg::AddPhase { phase: late() };
+        execute(deps.as_mut(), env_before_start(), mock_info("admin", &[]), msg).unwrap();
+        let allowlist: AllowlistResponse = from_json(
+            query(deps.as_ref(), mock_env(), QueryMsg::PhaseAllowlist {
+                phase_name: "Late".to_string(),
+                start_after: None,
+                limit: None,
+            }).unwrap()
+        ).unwrap();
+        assert!(allowlist.addresses.is_empty());
+
+        let deposit = |address: &str, env: Env, deps: DepsMut| {
+            let info = mock_info(address, &coins(PRICE, "inj"));
+            execute(deps, env, info, deposit_msg(1))
+        };
+        let err = deposit("addr2", mock_env(), deps.as_mut()).unwrap_err();
+        assert_eq!(err, ContractError::NotInPhase { phase: "OG".to_string() });
+        deposit("addr1", mock_env(), deps.as_mut()).unwrap();
+
+        // Without a rollover policy the previous phase's allowlist is not admitted
+        deposit("addr4", env_at(150), deps.as_mut()).unwrap();
+        let err = deposit("addr3", env_at(150), deps.as_mut()).unwrap_err();
+        assert_eq!(err, ContractError::NotInPhase { phase: "WL".to_string() });
+    }
+
+    #[test]
+    fn merkle_allowlist_test() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let start = mock_env().block.time.seconds();
+
+        let allocations = [("addr1", 3u64), ("addr2", 10), ("addr3", 1)];
+        let tree = MerkleTree::new(
+            allocations
+                .iter()
+                .map(|(address, allocation)| leaf_hash(address, *allocation))
+                .collect()
+        );
+        let proof_for = |index: usize| AllocationProof {
+            allocation: allocations[index].1,
+            proof: tree.proof(index).iter().map(hex::encode).collect(),
+        };
+
+        let mut phase = public_phase("OG", start, start + 600, 1000);
+        phase.merkle_root = Some(hex::encode(tree.root().unwrap()));
+        instantiate_launchpad(deps.as_mut(), vec![phase], None);
+
+        // A bad root is refused when configuring phases
+        let mut invalid = public_phase("Public", start + 601, start + 700, 1000);
+        invalid.merkle_root = Some("not a root".to_string());
+        let msg = ExecuteMsg::AddPhase { phase: invalid };
+        let err = execute(deps.as_mut(), env_before_start(), mock_info("admin", &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::InvalidMerkleRoot {});
+
+        let deposit = |deps: DepsMut, address: &str, amount: u64, phase_proof, whitelist_proof| {
+            let info = mock_info(address, &coins((amount as u128) * PRICE, "inj"));
+            let authorization = None;
+            let msg = ExecuteMsg::Deposit { amount, phase_proof, whitelist_proof, authorization };
+            execute(deps, mock_env(), info, msg)
+        };
+
+        // The root makes the phase restricted
+        let err = deposit(deps.as_mut(), "addr4", 1, None, None).unwrap_err();
+        assert_eq!(err, ContractError::NotInPhase { phase: "OG".to_string() });
+
+        // The proven allocation replaces the phase limit
+        deposit(deps.as_mut(), "addr1", 2, Some(proof_for(0)), None).unwrap();
+        let err = deposit(deps.as_mut(), "addr1", 2, Some(proof_for(0)), None).unwrap_err();
+        assert_eq!(err, ContractError::PhaseLimitReached { phase: "OG".to_string() });
+
+        // Proofs are bound to the address they were issued for
+        let err = deposit(deps.as_mut(), "addr2", 1, Some(proof_for(0)), None).unwrap_err();
+        assert_eq!(err, ContractError::InvalidMerkleProof {});
+        deposit(deps.as_mut(), "addr2", 10, Some(proof_for(1)), None).unwrap();
+
+        // Without a whitelist root whitelist proofs are refused
+        let err = deposit(deps.as_mut(), "addr3", 5, Some(proof_for(2)), Some(proof_for(2)));
+        assert_eq!(err.unwrap_err(), ContractError::InvalidMerkleProof {});
+
+        let whitelist_tree = MerkleTree::new(vec![leaf_hash("addr3", 5)]);
+        let root = hex::encode(whitelist_tree.root().unwrap());
+        let msg = update_config_msg(json!({ "whitelist_merkle_root": root }));
+        execute(deps.as_mut(), env_at(700), mock_info("admin", &[]), msg).unwrap();
+
+        // The whitelist amount overrides the phase allocation
+        let whitelist_proof = AllocationProof { allocation: 5, proof: vec![] };
+        deposit(deps.as_mut(), "addr3", 5, Some(proof_for(2)), Some(whitelist_proof)).unwrap();
+        let usr = USER_INFO.load(deps.as_ref().storage, Addr::unchecked("addr3")).unwrap();
+        assert_eq!(usr.amount, 5);
+    }
+
+    #[test]
+    fn signed_purchase_test() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let start = mock_env().block.time.seconds();
+        let mut phase = public_phase("KYC", start, start + 600, 1000);
+        phase.signature_required = true;
+        instantiate_launchpad(deps.as_mut(), vec![phase], None);
+
+        let signer = SigningKey::from_slice(&[7u8; 32]).unwrap();
+        let signer_pubkey = Binary::from(signer.verifying_key().to_encoded_point(true).as_bytes());
+        let msg = update_config_msg(json!({ "signer_pubkey": signer_pubkey }));
+        execute(deps.as_mut(), env_before_start(), mock_info("admin", &[]), msg).unwrap();
+        let whitelist = vec![WhitelistUser { address: Addr::unchecked("addr3"), amount: 50 }];
+        add_whitelist(deps.as_mut(), "admin".to_string(), whitelist);
 
-        // claim_tokens(deps.as_mut(), "admin".to_string());
+        let authorize = |buyer: &str, max_amount: u64, expiry: u64, nonce: u64| {
+            let payload = AuthorizationPayload {
+                contract: mock_env().contract.address.to_string(),
+                buyer: buyer.to_string(),
+                phase: "KYC".to_string(),
+                max_amount,
+                expiry,
+                nonce,
+            };
+            let signature: Signature = signer.sign(&to_json_vec(&payload).unwrap());
+            PurchaseAuthorization {
+                max_amount,
+                expiry,
+                nonce,
+                signature: Binary::from(&signature.to_bytes()[..]),
+            }
+        };
+        let deposit = |deps: DepsMut, buyer: &str, amount: u64, authorization| {
+            let info = mock_info(buyer, &coins((amount as u128) * PRICE, "inj"));
+            let msg = ExecuteMsg::Deposit {
+                amount,
+                phase_proof: None,
+                whitelist_proof: None,
+                authorization,
+            };
+            execute(deps, mock_env(), info, msg)
+        };
+
+        let err = deposit(deps.as_mut(), "addr1", 1, None).unwrap_err();
+        assert_eq!(err, ContractError::AuthorizationRequired { phase: "KYC".to_string() });
+
+        deposit(deps.as_mut(), "addr1", 3, Some(authorize("addr1", 5, start + 10, 1))).unwrap();
+
+        // Nonces are single use
+        let err = deposit(deps.as_mut(), "addr1", 1, Some(authorize("addr1", 5, start + 10, 1)));
+        assert_eq!(err.unwrap_err(), ContractError::NonceUsed { nonce: 1 });
+
+        // The signed amount caps the buyer's purchases in the phase
+        let err = deposit(deps.as_mut(), "addr1", 3, Some(authorize("addr1", 5, start + 10, 2)));
+        assert_eq!(err.unwrap_err(), ContractError::PhaseLimitReached { phase: "KYC".to_string() });
+
+        // Even for whitelisted buyers allowed more
+        let err = deposit(deps.as_mut(), "addr3", 10, Some(authorize("addr3", 5, start + 10, 5)));
+        assert_eq!(err.unwrap_err(), ContractError::ExceedsLimit { amount: 10, limit: 5 });
+
+        // Authorizations are bound to their buyer and expiry
+        let err = deposit(deps.as_mut(), "addr2", 1, Some(authorize("addr1", 5, start + 10, 3)));
+        assert_eq!(err.unwrap_err(), ContractError::InvalidSignature {});
+        let err = deposit(deps.as_mut(), "addr2", 1, Some(authorize("addr2", 5, start - 1, 4)));
+        assert_eq!(err.unwrap_err(), ContractError::AuthorizationExpired {});
+    }
+
+    #[test]
+    fn pro_rata_settle_test() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let start = mock_env().block.time.seconds();
+        let mut phase = public_phase("Fair", start, start + 100, 100);
+        phase.mode = PhaseMode::ProRata;
+        instantiate_launchpad(deps.as_mut(), vec![phase], Some(Uint128::new(100 * PRICE)));
+
+        // Commitments are accepted past the supply
+        for (address, amount) in [("addr1", 100u64), ("addr2", 60), ("addr3", 40)] {
+            let info = mock_info(address, &coins((amount as u128) * PRICE, "inj"));
+            execute(deps.as_mut(), mock_env(), info, deposit_msg(amount)).unwrap();
+        }
+        let state = STATE.load(deps.as_ref().storage).unwrap();
+        assert_eq!(state.phases[0].total_committed, 200);
+        assert_eq!(state.phases[0].total_minted, 0);
+
+        let settle = |deps: DepsMut, env: Env, address: &str| {
+            let msg = ExecuteMsg::Settle { address: Some(address.to_string()) };
+            execute(deps, env, mock_info("anyone", &[]), msg)
+        };
+        let err = settle(deps.as_mut(), mock_env(), "addr1").unwrap_err();
+        assert_eq!(err, ContractError::NothingToSettle {});
+
+        // Half of each commitment is allocated, the other half refunded
+        let res = settle(deps.as_mut(), env_at(101), "addr2").unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr2".to_string(),
+                amount: coins(30 * PRICE, "inj"),
+            })
+        );
+        let usr = USER_INFO.load(deps.as_ref().storage, Addr::unchecked("addr2")).unwrap();
+        assert_eq!(usr.tokens, Uint128::new(30));
+        assert_eq!(usr.paid, Uint128::new(30 * PRICE));
+        assert!(usr.committed.is_empty());
+        let err = settle(deps.as_mut(), env_at(101), "addr2").unwrap_err();
+        assert_eq!(err, ContractError::NothingToSettle {});
+
+        // Only settled funds can be withdrawn, the soft cap counts the final allocation
+        execute(deps.as_mut(), env_at(601), mock_info("admin", &[]), ExecuteMsg::Finalize {}).unwrap();
+        let res = execute(deps.as_mut(), env_at(601), mock_info("admin", &[]), ExecuteMsg::Withdraw {});
+        let withdrawn: Uint128 = res
+            .unwrap()
+            .messages.iter()
+            .map(|msg| {
+                match &msg.msg {
+                    CosmosMsg::Bank(BankMsg::Send { amount, .. }) => amount[0].amount,
+                    _ => Uint128::zero(),
+                }
+            })
+            .sum();
+        assert_eq!(withdrawn, Uint128::new(30 * PRICE));
+
+        // Claims wait for the commitment to be settled
+        STATE.update(deps.as_mut().storage, |mut state| -> cosmwasm_std::StdResult<_> {
+            state.token_info.denom = Some("sale_token".to_string());
+            state.token_info.decimals = Some(6);
+            Ok(state)
+        }).unwrap();
+        fund_sale(deps.as_mut(), 5000 * 1000000).unwrap();
+        execute(deps.as_mut(), env_at(601), mock_info("admin", &[]), ExecuteMsg::FlipClaimStatus {}).unwrap();
+        let claim = ExecuteMsg::ClaimTokens {};
+        let err = execute(deps.as_mut(), env_at(601), mock_info("addr1", &[]), claim.clone()).unwrap_err();
+        assert_eq!(err, ContractError::NotSettled {});
+
+        settle(deps.as_mut(), env_at(601), "addr1").unwrap();
+        let res = execute(deps.as_mut(), env_at(601), mock_info("addr1", &[]), claim).unwrap();
+        assert_eq!(cw20_transfer_amount(&res), Uint128::new(50 * 1000000));
+        settle(deps.as_mut(), env_at(601), "addr3").unwrap();
+        let state = STATE.load(deps.as_ref().storage).unwrap();
+        assert_eq!(state.phases[0].total_minted, 100);
+        assert_eq!(state.phases[0].total_raised, Uint128::new(100 * PRICE));
+    }
+
+    #[test]
+    fn dutch_auction_test() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let start = mock_env().block.time.seconds();
+        let mut phase = public_phase("Auction", start, start + 100, 10);
+        phase.price_per_token = Uint128::new(10 * PRICE);
+        phase.mode = PhaseMode::DutchAuction { floor_price: Uint128::new(2 * PRICE), step: None };
+        instantiate_launchpad(deps.as_mut(), vec![phase.clone()], None);
+
+        // A zero step never decays
+        phase.name = "Stuck".to_string();
+        phase.mode = PhaseMode::DutchAuction { floor_price: Uint128::new(2 * PRICE), step: Some(0) };
+        let msg = ExecuteMsg::AddPhase { phase: phase.clone() };
+        let err = execute(deps.as_mut(), env_before_start(), mock_info("admin", &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::InvalidAuction {});
+
+        // Gated auctions are validated too, whichever way the phase is configured
+        phase.name = "Gated".to_string();
+        phase.merkle_root = Some(hex::encode([0u8; 32]));
+        phase.mode = PhaseMode::DutchAuction { floor_price: Uint128::new(20 * PRICE), step: None };
+        let msg = ExecuteMsg::AddPhase { phase: phase.clone() };
+        let err = execute(deps.as_mut(), env_before_start(), mock_info("admin", &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::InvalidAuction {});
+        let msg = update_config_msg(json!({ "phases": vec![phase.clone()] }));
+        let err = execute(deps.as_mut(), env_before_start(), mock_info("admin", &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::InvalidAuction {});
+        let mut other = mock_dependencies_with_balance(&[]);
+        let msg = InstantiateMsg {
+            admin: "admin".to_string(),
+            start_time: start,
+            end_time: start + 600,
+            phases: Some(vec![phase]),
+            buy_denom: "inj".to_string(),
+            buy_token_type: "native".into(),
+            tokens_per_buy: Uint128::new(1),
+            token_info: TokenInfo {
+                name: "TEST".to_string(),
+                symbol: "TEST".to_string(),
+                description: "TEST".to_string(),
+                denom: None,
+                decimals: None,
+                supply: 5000,
+                kind: SaleTokenKind::Cw20,
+            },
+            fees_wallet: "fees_wallet".to_string(),
+            fees_percentage: None,
+            withdraw_wallet: "withdraw_wallet".to_string(),
+            whitelist: None,
+            soft_cap: None,
+            vesting: None,
+            whitelist_merkle_root: None,
+            signer_pubkey: None,
+        };
+        let err = instantiate(other.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::InvalidAuction {});
+
+        let quote = |deps: Deps, seconds: u64| -> PriceQuoteResponse {
+            let msg = QueryMsg::PriceQuote { phase_name: "Auction".to_string(), amount: 2 };
+            from_json(query(deps, env_at(seconds), msg).unwrap()).unwrap()
+        };
+        assert_eq!(quote(deps.as_ref(), 0).price_per_token, Uint128::new(10 * PRICE));
+        assert_eq!(quote(deps.as_ref(), 50).total_price, Uint128::new(12 * PRICE));
+        assert_eq!(quote(deps.as_ref(), 200).price_per_token, Uint128::new(2 * PRICE));
+
+        let deposit = |deps: DepsMut, address: &str, amount: u64, paid: u128, seconds: u64| {
+            let info = mock_info(address, &coins(paid, "inj"));
+            execute(deps, env_at(seconds), info, deposit_msg(amount))
+        };
+        let err = deposit(deps.as_mut(), "addr1", 4, 24 * PRICE, 0).unwrap_err();
+        assert_eq!(err, ContractError::WrongPayment {
+            expected: Uint128::new(40 * PRICE),
+            got: Uint128::new(24 * PRICE),
+        });
+        deposit(deps.as_mut(), "addr1", 4, 40 * PRICE, 0).unwrap();
+
+        // Selling out sets the clearing price
+        deposit(deps.as_mut(), "addr2", 6, 36 * PRICE, 50).unwrap();
+        let state = STATE.load(deps.as_ref().storage).unwrap();
+        assert_eq!(state.phases[0].clearing_price, Some(Uint128::new(6 * PRICE)));
+        let err = deposit(deps.as_mut(), "addr3", 1, 5 * PRICE, 60).unwrap_err();
+        assert_eq!(err, ContractError::Overbuy {});
+
+        // Early buyers get back what they paid above the clearing price
+        let msg = ExecuteMsg::Settle { address: None };
+        let res = execute(deps.as_mut(), env_at(101), mock_info("addr1", &[]), msg).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr1".to_string(),
+                amount: coins(16 * PRICE, "inj"),
+            })
+        );
+        let usr = USER_INFO.load(deps.as_ref().storage, Addr::unchecked("addr1")).unwrap();
+        assert_eq!(usr.paid, Uint128::new(24 * PRICE));
+        assert_eq!(usr.tokens, Uint128::new(4));
+
+        let msg = ExecuteMsg::Settle { address: None };
+        let res = execute(deps.as_mut(), env_at(101), mock_info("addr2", &[]), msg).unwrap();
+        assert!(res.messages.is_empty());
+        let state = STATE.load(deps.as_ref().storage).unwrap();
+        assert_eq!(state.phases[0].total_raised, Uint128::new(60 * PRICE));
+    }
+
+    #[test]
+    fn curve_pricing_test() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let start = mock_env().block.time.seconds();
+        let tier = |supply: u64, price: u128| PriceTier { supply, price: Uint128::new(price * PRICE) };
+        let mut tiered = public_phase("Tiered", start, start + 100, 20);
+        tiered.mode = PhaseMode::Tiered { tiers: vec![tier(3, 1), tier(2, 2), tier(5, 3)] };
+        let mut curve = public_phase("Curve", start + 101, start + 200, 20);
+        curve.mode = PhaseMode::BondingCurve { slope: Uint128::new(PRICE) };
+        instantiate_launchpad(deps.as_mut(), vec![tiered.clone(), curve], None);
+
+        tiered.name = "Empty".to_string();
+        tiered.mode = PhaseMode::Tiered { tiers: vec![] };
+        let msg = ExecuteMsg::AddPhase { phase: tiered };
+        let err = execute(deps.as_mut(), env_before_start(), mock_info("admin", &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::InvalidTiers {});
+
+        let quote = |deps: Deps, phase_name: &str, amount: u64, seconds: u64| {
+            let msg = QueryMsg::PriceQuote { phase_name: phase_name.to_string(), amount };
+            let quote: PriceQuoteResponse = from_json(
+                query(deps, env_at(seconds), msg).unwrap()
+            ).unwrap();
+            quote.total_price.u128() / PRICE
+        };
+        let deposit = |deps: DepsMut, amount: u64, paid: u128, seconds: u64| {
+            let info = mock_info("addr1", &coins(paid * PRICE, "inj"));
+            execute(deps, env_at(seconds), info, deposit_msg(amount))
+        };
+
+        // A deposit crossing tiers is priced piecewise
+        assert_eq!(quote(deps.as_ref(), "Tiered", 4, 0), 3 + 2);
+        deposit(deps.as_mut(), 4, 5, 0).unwrap();
+        assert_eq!(quote(deps.as_ref(), "Tiered", 1, 0), 2);
+        // Units past the last tier keep its price
+        deposit(deps.as_mut(), 7, 2 + 15 + 3, 0).unwrap();
+
+        // Each unit on the curve costs one more than the previous one
+        assert_eq!(quote(deps.as_ref(), "Curve", 3, 150), 1 + 2 + 3);
+        deposit(deps.as_mut(), 3, 6, 150).unwrap();
+        assert_eq!(quote(deps.as_ref(), "Curve", 2, 150), 4 + 5);
+        let err = deposit(deps.as_mut(), 2, 8, 150).unwrap_err();
+        assert_eq!(err, ContractError::WrongPayment {
+            expected: Uint128::new(9 * PRICE),
+            got: Uint128::new(8 * PRICE),
+        });
+
+        let state = STATE.load(deps.as_ref().storage).unwrap();
+        assert_eq!(state.phases[0].total_raised, Uint128::new(25 * PRICE));
+        assert_eq!(state.phases[1].total_raised, Uint128::new(6 * PRICE));
+    }
+
+    #[test]
+    fn simulate_deposit_test() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let start = mock_env().block.time.seconds();
+        instantiate_launchpad(deps.as_mut(), vec![public_phase("OG", start, start + 600, 1000)], None);
+        add_to_phase_allowlist(deps.as_mut(), "OG", &["addr1", "addr2"]);
+        let whitelist = vec![WhitelistUser { address: Addr::unchecked("addr2"), amount: 150 }];
+        let msg = ExecuteMsg::AddWhitelist { whitelist };
+        execute(deps.as_mut(), env_before_start(), mock_info("admin", &[]), msg).unwrap();
+
+        let simulate = |deps: Deps, address: &str, amount: u64, seconds: u64| {
+            let msg = QueryMsg::SimulateDeposit {
+                address: address.to_string(),
+                amount,
+                phase_proof: None,
+                whitelist_proof: None,
+                authorization: None,
+            };
+            from_json::<SimulateDepositResponse>(query(deps, env_at(seconds), msg).unwrap()).unwrap()
+        };
+
+        let simulation = simulate(deps.as_ref(), "addr1", 5, 0);
+        assert_eq!(simulation.phase, Some("OG".to_string()));
+        assert_eq!(simulation.required_funds, coin(5 * PRICE, "inj"));
+        assert_eq!(simulation.tokens, Uint128::new(5));
+        assert_eq!(simulation.remaining_supply, 1000);
+        assert_eq!(simulation.remaining_phase_allowance, 100);
+        assert_eq!(simulation.rejection, None);
+
+        let simulation = simulate(deps.as_ref(), "addr3", 5, 0);
+        assert_eq!(simulation.rejection, Some(DepositRejection::NotInPhase));
+        assert_eq!(simulation.error, Some("Unauthorized: not in phase OG".to_string()));
+
+        // The simulation follows the state left by previous deposits
+        let info = mock_info("addr1", &coins(98 * PRICE, "inj"));
+        execute(deps.as_mut(), mock_env(), info, deposit_msg(98)).unwrap();
+        let simulation = simulate(deps.as_ref(), "addr1", 5, 0);
+        assert_eq!(simulation.rejection, Some(DepositRejection::PhaseLimitReached));
+        assert_eq!(simulation.remaining_phase_allowance, 2);
+        assert_eq!(simulation.remaining_supply, 902);
+
+        // Whitelisted amounts lift the phase limit
+        let simulation = simulate(deps.as_ref(), "addr2", 120, 0);
+        assert_eq!(simulation.whitelist_allowance, 150);
+        assert_eq!(simulation.rejection, None);
+
+        let simulation = simulate(deps.as_ref(), "addr1", 1, 700);
+        assert_eq!(simulation.phase, None);
+        assert_eq!(simulation.rejection, Some(DepositRejection::SaleEnded));
+    }
+
+    #[test]
+    fn sale_status_test() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let start = mock_env().block.time.seconds();
+        instantiate_launchpad(deps.as_mut(), vec![], Some(Uint128::new(10 * PRICE)));
+
+        let status = |deps: Deps, env: Env| {
+            let res = query(deps, env, QueryMsg::Status {}).unwrap();
+            from_json::<StatusResponse>(res).unwrap().status
+        };
+        assert_eq!(status(deps.as_ref(), env_before_start()), SaleStatus::Draft);
+
+        let msg = ExecuteMsg::AddPhase { phase: public_phase("Public", start, start + 600, 1000) };
+        execute(deps.as_mut(), env_before_start(), mock_info("admin", &[]), msg).unwrap();
+        assert_eq!(status(deps.as_ref(), env_before_start()), SaleStatus::Upcoming);
+        assert_eq!(status(deps.as_ref(), mock_env()), SaleStatus::Active {
+            phase: Some("Public".to_string()),
+        });
+
+        // Phases are frozen once the sale is live
+        let msg = ExecuteMsg::RemovePhase { name: "Public".to_string() };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::SaleStarted {});
+
+        let info = mock_info("addr1", &coins(20 * PRICE, "inj"));
+        execute(deps.as_mut(), mock_env(), info, deposit_msg(20)).unwrap();
+        let finalize = ExecuteMsg::Finalize {};
+        let err = execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), finalize.clone());
+        assert_eq!(err.unwrap_err(), ContractError::SaleNotEnded {});
+
+        // Cancelling refunds every buyer, even above the soft cap
+        execute(deps.as_mut(), env_at(10), mock_info("admin", &[]), ExecuteMsg::Cancel {}).unwrap();
+        assert_eq!(status(deps.as_ref(), env_at(10)), SaleStatus::Cancelled);
+        let cancelled = ContractError::InvalidStatus { status: "cancelled".to_string() };
+        let info = mock_info("addr2", &coins(PRICE, "inj"));
+        let err = execute(deps.as_mut(), env_at(10), info, deposit_msg(1)).unwrap_err();
+        assert_eq!(err, cancelled);
+
+        let refund = ExecuteMsg::ClaimRefund {};
+        let res = execute(deps.as_mut(), env_at(10), mock_info("addr1", &[]), refund).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr1".to_string(),
+                amount: coins(20 * PRICE, "inj"),
+            })
+        );
+        let err = execute(deps.as_mut(), env_at(601), mock_info("admin", &[]), finalize).unwrap_err();
+        assert_eq!(err, cancelled);
+    }
+
+    #[test]
+    fn pause_test() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let start = mock_env().block.time.seconds();
+        instantiate_launchpad(
+            deps.as_mut(),
+            vec![
+                public_phase("OG", start, start + 100, 1000),
+                public_phase("Public", start + 101, start + 600, 1000)
+            ],
+            None
+        );
+        let admin = || mock_info("admin", &[]);
+        let deposit = |deps: DepsMut, seconds: u64| {
+            let info = mock_info("addr1", &coins(PRICE, "inj"));
+            execute(deps, env_at(seconds), info, deposit_msg(1))
+        };
+        deposit(deps.as_mut(), 0).unwrap();
+
+        let msg = ExecuteMsg::Pause { reason: "price mistake".to_string() };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("addr1", &[]), msg.clone()).unwrap_err();
+        assert_eq!(err, ContractError::MissingRole { role: "pauser".to_string() });
+        execute(deps.as_mut(), mock_env(), admin(), msg).unwrap();
+
+        let status: StatusResponse = from_json(
+            query(deps.as_ref(), mock_env(), QueryMsg::Status {}).unwrap()
+        ).unwrap();
+        assert_eq!(status.status, SaleStatus::Paused);
+        assert_eq!(status.pause_reason, Some("price mistake".to_string()));
+        let err = deposit(deps.as_mut(), 10).unwrap_err();
+        assert_eq!(err, ContractError::Paused { reason: "price mistake".to_string() });
+
+        // The mistake is fixed while paused, without dropping what was already sold
+        let update_phase = |price: u128, supply: u64| ExecuteMsg::UpdatePhase {
+            name: "OG".to_string(),
+            start_time: None,
+            end_time: None,
+            price_per_token: Some(Uint128::new(price)),
+            supply: Some(supply),
+            limit: None,
+            merkle_root: None,
+        };
+        let err = execute(deps.as_mut(), mock_env(), admin(), update_phase(2 * PRICE, 0)).unwrap_err();
+        assert_eq!(err, ContractError::SupplyBelowSold { sold: 1 });
+        execute(deps.as_mut(), mock_env(), admin(), update_phase(2 * PRICE, 1000)).unwrap();
+
+        execute(deps.as_mut(), mock_env(), admin(), ExecuteMsg::Unpause {}).unwrap();
+        let err = execute(deps.as_mut(), mock_env(), admin(), ExecuteMsg::Unpause {}).unwrap_err();
+        assert_eq!(err, ContractError::NotPaused {});
+        deposit(deps.as_mut(), 10).unwrap_err();
+        let info = mock_info("addr1", &coins(2 * PRICE, "inj"));
+        execute(deps.as_mut(), env_at(10), info, deposit_msg(1)).unwrap();
+
+        // A paused phase only halts its own deposits
+        let msg = ExecuteMsg::PausePhase { name: "OG".to_string(), reason: "exploit".to_string() };
+        execute(deps.as_mut(), mock_env(), admin(), msg).unwrap();
+        let phase_paused = ContractError::PhasePaused {
+            phase: "OG".to_string(),
+            reason: "exploit".to_string(),
+        };
+        assert_eq!(deposit(deps.as_mut(), 20).unwrap_err(), phase_paused);
+        deposit(deps.as_mut(), 200).unwrap();
+
+        // Claims stay locked for the buyers of the paused phase
+        STATE.update(deps.as_mut().storage, |mut state| -> cosmwasm_std::StdResult<_> {
+            state.token_info.denom = Some("sale_token".to_string());
+            state.token_info.decimals = Some(6);
+            Ok(state)
+        }).unwrap();
+        fund_sale(deps.as_mut(), 5000 * 1000000).unwrap();
+        execute(deps.as_mut(), env_at(601), admin(), ExecuteMsg::Finalize {}).unwrap();
+        execute(deps.as_mut(), env_at(601), admin(), ExecuteMsg::FlipClaimStatus {}).unwrap();
+        let claim = ExecuteMsg::ClaimTokens {};
+        let err = execute(deps.as_mut(), env_at(601), mock_info("addr1", &[]), claim.clone());
+        assert_eq!(err.unwrap_err(), phase_paused);
+
+        let msg = ExecuteMsg::UnpausePhase { name: "OG".to_string() };
+        execute(deps.as_mut(), env_at(601), admin(), msg).unwrap();
+        let res = execute(deps.as_mut(), env_at(601), mock_info("addr1", &[]), claim).unwrap();
+        assert_eq!(cw20_transfer_amount(&res), Uint128::new(3_000_000));
+    }
+
+    #[test]
+    fn roles_test() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let start = mock_env().block.time.seconds();
+        instantiate_launchpad(deps.as_mut(), vec![public_phase("Public", start, start + 600, 1000)], None);
+
+        let grant = |address: &str, role: Role| ExecuteMsg::GrantRole {
+            address: address.to_string(),
+            role,
+        };
+        let err = execute(
+            deps.as_mut(),
+            env_before_start(),
+            mock_info("manager", &[]),
+            grant("manager", Role::Owner)
+        ).unwrap_err();
+        assert_eq!(err, ContractError::MissingRole { role: "owner".to_string() });
+        let msg = grant("manager", Role::WhitelistManager);
+        execute(deps.as_mut(), env_before_start(), mock_info("admin", &[]), msg).unwrap();
+        let msg = grant("manager", Role::Pauser);
+        execute(deps.as_mut(), env_before_start(), mock_info("admin", &[]), msg).unwrap();
+        let msg = grant("treasurer", Role::Treasurer);
+        execute(deps.as_mut(), env_before_start(), mock_info("admin", &[]), msg).unwrap();
+
+        let roles: RolesResponse = from_json(
+            query(deps.as_ref(), mock_env(), QueryMsg::Roles { start_after: None, limit: None }).unwrap()
+        ).unwrap();
+        assert_eq!(roles.holders.len(), 2);
+        assert_eq!(roles.holders[0].roles, vec![Role::WhitelistManager, Role::Pauser]);
+
+        // The whitelist manager maintains allowlists but cannot touch the funds
+        let whitelist = vec![WhitelistUser { address: Addr::unchecked("addr1"), amount: 150 }];
+        let msg = ExecuteMsg::AddWhitelist { whitelist };
+        execute(deps.as_mut(), env_before_start(), mock_info("manager", &[]), msg).unwrap();
+        let msg = ExecuteMsg::RemovePhase { name: "Public".to_string() };
+        let err = execute(deps.as_mut(), env_before_start(), mock_info("manager", &[]), msg);
+        assert_eq!(err.unwrap_err(), ContractError::MissingRole { role: "phase_operator".to_string() });
+
+        let info = mock_info("addr1", &coins(10 * PRICE, "inj"));
+        execute(deps.as_mut(), mock_env(), info, deposit_msg(10)).unwrap();
+        execute(deps.as_mut(), env_at(601), mock_info("admin", &[]), ExecuteMsg::Finalize {}).unwrap();
+        let err = execute(deps.as_mut(), env_at(601), mock_info("manager", &[]), ExecuteMsg::Withdraw {});
+        assert_eq!(err.unwrap_err(), ContractError::MissingRole { role: "treasurer".to_string() });
+        execute(deps.as_mut(), env_at(601), mock_info("treasurer", &[]), ExecuteMsg::Withdraw {}).unwrap();
+
+        // Revoking the last role drops the holder
+        let msg = ExecuteMsg::RevokeRole { address: "treasurer".to_string(), role: Role::Treasurer };
+        execute(deps.as_mut(), env_at(601), mock_info("admin", &[]), msg).unwrap();
+        let roles: RolesResponse = from_json(
+            query(deps.as_ref(), mock_env(), QueryMsg::Roles { start_after: None, limit: None }).unwrap()
+        ).unwrap();
+        assert_eq!(roles.holders.len(), 1);
+        assert_eq!(roles.holders[0].address, Addr::unchecked("manager"));
+    }
+
+    #[test]
+    fn admin_transfer_test() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        instantiate_launchpad(deps.as_mut(), vec![], None);
+
+        // Malformed addresses are refused instead of locking the contract
+        let propose = |address: &str, expiry: Option<u64>| ExecuteMsg::ProposeAdmin {
+            address: address.to_string(),
+            expiry,
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), propose("New_Admin", None));
+        assert!(matches!(res.unwrap_err(), ContractError::Std(_)));
+
+        let expiry = mock_env().block.time.seconds() + 100;
+        let msg = propose("new_admin", Some(expiry));
+        execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+        let pending: PendingAdminResponse = from_json(
+            query(deps.as_ref(), mock_env(), QueryMsg::PendingAdmin {}).unwrap()
+        ).unwrap();
+        assert_eq!(pending.pending_admin.unwrap().address, Addr::unchecked("new_admin"));
+
+        let accept = ExecuteMsg::AcceptAdmin {};
+        let err = execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), accept.clone());
+        assert_eq!(err.unwrap_err(), ContractError::NotPendingAdmin {});
+        let err = execute(deps.as_mut(), env_at(101), mock_info("new_admin", &[]), accept.clone());
+        assert_eq!(err.unwrap_err(), ContractError::AdminProposalExpired {});
+
+        // Cancelling drops the proposal
+        let cancel = ExecuteMsg::CancelAdminProposal {};
+        execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), cancel.clone()).unwrap();
+        let err = execute(deps.as_mut(), mock_env(), mock_info("new_admin", &[]), accept.clone());
+        assert_eq!(err.unwrap_err(), ContractError::NoPendingAdmin {});
+
+        let msg = propose("new_admin", None);
+        execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+        execute(deps.as_mut(), env_at(1000), mock_info("new_admin", &[]), accept).unwrap();
+        let state = STATE.load(deps.as_ref().storage).unwrap();
+        assert_eq!(state.admin, Addr::unchecked("new_admin"));
+        let err = execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), cancel).unwrap_err();
+        assert_eq!(err, ContractError::MissingRole { role: "owner".to_string() });
+    }
+
+    #[test]
+    fn tokenfactory_test() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let start = mock_env().block.time.seconds();
+        instantiate_launchpad(deps.as_mut(), vec![public_phase("Public", start, start + 600, 1000)], None);
+        set_sale_token(deps.as_mut(), SaleTokenKind::TokenFactory);
+
+        let info = mock_info("addr1", &coins(10 * PRICE, "inj"));
+        execute(deps.as_mut(), mock_env(), info, deposit_msg(10)).unwrap();
+
+        // Finalizing creates the denom with the token metadata, paying the creation fee
+        let ended = env_at(601);
+        let err = execute(deps.as_mut(), ended.clone(), mock_info("admin", &[]), ExecuteMsg::Finalize {});
+        assert_eq!(err.unwrap_err(), ContractError::DenomCreationFee {
+            fee: format!("{}inj", DENOM_CREATION_FEE),
+        });
+        let info = mock_info("admin", &coins(DENOM_CREATION_FEE, "inj"));
+        let res = execute(deps.as_mut(), ended.clone(), info, ExecuteMsg::Finalize {}).unwrap();
+        assert_eq!(res.messages.len(), 2);
+        assert_eq!(
+            serde_json::to_value(&res.messages[0].msg).unwrap(),
+            json!({
+                "custom": {
+                    "route": "tokenfactory",
+                    "msg_data": {
+                        "create_denom": { "sender": "cosmos2contract", "subdenom": "TEST" },
+                    },
+                },
+            })
+        );
+        let denom = "factory/cosmos2contract/TEST".to_string();
+        match &res.messages[1].msg {
+            CosmosMsg::Custom(InjectiveMsgWrapper {
+                msg_data: InjectiveMsg::SetDenomMetadata { metadata, .. },
+                ..
+            }) => {
+                assert_eq!(metadata.base, denom);
+                assert_eq!(metadata.display, "TEST");
+                assert_eq!(metadata.decimals, 6);
+            }
+            other => panic!("unexpected message {:?}", other),
+        }
+        let state = STATE.load(deps.as_ref().storage).unwrap();
+        assert_eq!(state.token_info.denom, Some(denom.clone()));
+
+        // Claims mint the tokens to the buyer
+        execute(deps.as_mut(), ended.clone(), mock_info("admin", &[]), ExecuteMsg::FlipClaimStatus {}).unwrap();
+        let claim = ExecuteMsg::ClaimTokens {};
+        let res = execute(deps.as_mut(), ended.clone(), mock_info("addr1", &[]), claim).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Custom(InjectiveMsgWrapper {
+                route: InjectiveRoute::Tokenfactory,
+                msg_data: InjectiveMsg::Mint {
+                    sender: "cosmos2contract".to_string(),
+                    amount: coin(10_000_000, denom),
+                    mint_to: "addr1".to_string(),
+                },
+            })
+        );
+
+        let withdraw_unsold = ExecuteMsg::WithdrawUnsold {};
+        let err = execute(deps.as_mut(), ended, mock_info("admin", &[]), withdraw_unsold).unwrap_err();
+        assert_eq!(err, ContractError::NothingToWithdraw {});
+    }
+
+    #[test]
+    fn cw20_mint_test() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let start = mock_env().block.time.seconds();
+        instantiate_launchpad(deps.as_mut(), vec![public_phase("Public", start, start + 600, 1000)], None);
+        set_sale_token(deps.as_mut(), SaleTokenKind::Cw20Minter { code_id: Some(7) });
+
+        let info = mock_info("addr1", &coins(10 * PRICE, "inj"));
+        execute(deps.as_mut(), mock_env(), info, deposit_msg(10)).unwrap();
+
+        // Finalizing instantiates the cw20 with the launchpad as minter, capped at the supply
+        let ended = env_at(601);
+        let res = execute(deps.as_mut(), ended.clone(), mock_info("admin", &[]), ExecuteMsg::Finalize {});
+        let res = res.unwrap();
+        assert_eq!(res.messages[0].reply_on, ReplyOn::Success);
+        match &res.messages[0].msg {
+            CosmosMsg::Wasm(WasmMsg::Instantiate { code_id, msg, .. }) => {
+                assert_eq!(*code_id, 7);
+                let msg: cw20_base::msg::InstantiateMsg = from_json(msg).unwrap();
+                assert_eq!(msg.symbol, "TEST");
+                let mint = msg.mint.unwrap();
+                assert_eq!(mint.minter, "cosmos2contract");
+                assert_eq!(mint.cap, Some(Uint128::new(5_000_000_000)));
+            }
+            other => panic!("unexpected message {:?}", other),
+        }
+
+        let result = SubMsgResult::Ok(SubMsgResponse {
+            events: vec![Event::new("instantiate").add_attribute("_contract_address", "sale_cw20")],
+            data: None,
+        });
+        let msg = Reply { id: res.messages[0].id, result };
+        reply(deps.as_mut(), ended.clone(), msg).unwrap();
+        let state = STATE.load(deps.as_ref().storage).unwrap();
+        assert_eq!(state.token_info.denom, Some("sale_cw20".to_string()));
+
+        // Claims mint to the buyer instead of transferring
+        execute(deps.as_mut(), ended.clone(), mock_info("admin", &[]), ExecuteMsg::FlipClaimStatus {}).unwrap();
+        let claim = ExecuteMsg::ClaimTokens {};
+        let res = execute(deps.as_mut(), ended, mock_info("addr1", &[]), claim).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "sale_cw20".to_string(),
+                msg: to_json_binary(
+                    &(Cw20ExecuteMsg::Mint {
+                        recipient: "addr1".to_string(),
+                        amount: Uint128::new(10_000_000),
+                    })
+                ).unwrap(),
+                funds: vec![],
+            })
+        );
+    }
+
+    #[test]
+    fn escrow_test() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let start = mock_env().block.time.seconds();
+        instantiate_launchpad(deps.as_mut(), vec![public_phase("Public", start, start + 600, 1000)], None);
+
+        let escrow = |deps: Deps| {
+            let res = query(deps, mock_env(), QueryMsg::GetLaunchpad {}).unwrap();
+            from_json::<LaunchpadResponse>(&res).unwrap().escrow
+        };
+        assert_eq!(fund_sale(deps.as_mut(), 1000).unwrap_err(), ContractError::TokenNotSettled {});
+        assert_eq!(escrow(deps.as_ref()), EscrowResponse {
+            escrowed: Uint128::zero(),
+            required: None,
+            funded: false,
+        });
+
+        STATE.update(deps.as_mut().storage, |mut state| -> cosmwasm_std::StdResult<_> {
+            state.token_info.denom = Some("sale_token".to_string());
+            state.token_info.decimals = Some(6);
+            Ok(state)
+        }).unwrap();
+
+        // Only the sale token is accepted, through the matching entry point
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "project".to_string(),
+            amount: Uint128::new(1000),
+            msg: to_json_binary(&(ReceiveMsg::FundSale {})).unwrap(),
+        });
+        let err = execute(deps.as_mut(), mock_env(), mock_info("fake_token", &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::WrongSaleToken {});
+        let info = mock_info("project", &coins(1000, "sale_token"));
+        let err = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::FundSale {}).unwrap_err();
+        assert_eq!(err, ContractError::WrongSaleToken {});
+
+        // The whole token sale supply is escrowed before buyers commit, not only the phases supply
+        let res = fund_sale(deps.as_mut(), 4000 * 1000000).unwrap();
+        assert_eq!(res.attributes[2].value, "4000000000");
+        let deposit = || deposit_msg(10);
+        let info = || mock_info("addr1", &coins(10 * PRICE, "inj"));
+        let err = execute(deps.as_mut(), mock_env(), info(), deposit()).unwrap_err();
+        assert_eq!(err, ContractError::Underfunded {
+            escrowed: Uint128::new(4000 * 1000000),
+            required: Uint128::new(5000 * 1000000),
+        });
+
+        fund_sale(deps.as_mut(), 1000 * 1000000).unwrap();
+        assert_eq!(escrow(deps.as_ref()), EscrowResponse {
+            escrowed: Uint128::new(5000 * 1000000),
+            required: Some(Uint128::new(5000 * 1000000)),
+            funded: true,
+        });
+        execute(deps.as_mut(), mock_env(), info(), deposit()).unwrap();
+        let ended = env_at(601);
+        execute(deps.as_mut(), ended.clone(), mock_info("admin", &[]), ExecuteMsg::Finalize {}).unwrap();
+
+        // The escrowed tokens pin the sale token
+        let mut token_info = STATE.load(deps.as_ref().storage).unwrap().token_info;
+        token_info.decimals = Some(18);
+        let msg = update_config_msg(json!({ "token_info": token_info }));
+        let err = execute(deps.as_mut(), ended.clone(), mock_info("admin", &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::SaleTokenLocked {});
+
+        let flip = ExecuteMsg::FlipClaimStatus {};
+        execute(deps.as_mut(), ended, mock_info("admin", &[]), flip).unwrap();
+    }
+
+    #[test]
+    fn supply_test() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let start = mock_env().block.time.seconds();
+        instantiate_launchpad(deps.as_mut(), vec![public_phase("OG", start, start + 100, 3000)], None);
+
+        let unallocated = |deps: Deps| {
+            let res = query(deps, mock_env(), QueryMsg::GetLaunchpad {}).unwrap();
+            from_json::<LaunchpadResponse>(&res).unwrap().unallocated_supply
+        };
+        assert_eq!(unallocated(deps.as_ref()), Uint128::new(2000));
+
+        // Phases can't sell more than the 5000 tokens of the sale supply
+        let exceeded = |allocated: u128| ContractError::SupplyExceeded {
+            allocated: Uint128::new(allocated),
+            supply: 5000,
+        };
+        let admin = || mock_info("admin", &[]);
+
+        // A new phase can't claim sales it never made
+        let mut presold = public_phase("Public", start + 101, start + 600, 2000);
+        presold.total_minted = 2000;
+        presold.total_raised = Uint128::new(2000 * PRICE);
+        let msg = ExecuteMsg::AddPhase { phase: presold };
+        let err = execute(deps.as_mut(), env_before_start(), admin(), msg).unwrap_err();
+        assert_eq!(err, ContractError::InvalidPhaseCounters {});
+
+        let msg = ExecuteMsg::AddPhase { phase: public_phase("Public", start + 101, start + 600, 2001) };
+        let err = execute(deps.as_mut(), env_before_start(), admin(), msg).unwrap_err();
+        assert_eq!(err, exceeded(5001));
+        let msg = ExecuteMsg::AddPhase { phase: public_phase("Public", start + 101, start + 600, 2000) };
+        execute(deps.as_mut(), env_before_start(), admin(), msg).unwrap();
+        assert_eq!(unallocated(deps.as_ref()), Uint128::zero());
+
+        let msg = ExecuteMsg::UpdatePhase {
+            name: "OG".to_string(),
+            start_time: None,
+            end_time: None,
+            price_per_token: None,
+            supply: Some(3500),
+            limit: None,
+            merkle_root: None,
+        };
+        let err = execute(deps.as_mut(), env_before_start(), admin(), msg).unwrap_err();
+        assert_eq!(err, exceeded(5500));
+
+        // Each unit sells `tokens_per_buy` tokens
+        let msg = update_config_msg(json!({ "tokens_per_buy": Uint128::new(2) }));
+        let err = execute(deps.as_mut(), env_before_start(), admin(), msg).unwrap_err();
+        assert_eq!(err, exceeded(10000));
+    }
+
+    #[test]
+    fn rollover_test() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let start = mock_env().block.time.seconds();
+        let phase = |name: &str, start_time: u64, end_time: u64, rollover: RolloverPolicy| Phase {
+            rollover,
+            ..public_phase(name, start_time, end_time, 1000)
+        };
+        instantiate_launchpad(
+            deps.as_mut(),
+            vec![
+                phase("OG", start, start + 100, RolloverPolicy::CarrySupplyAndAllowlist),
+                phase("WL", start + 101, start + 200, RolloverPolicy::ReturnToProject),
+                phase("Public", start + 201, start + 600, RolloverPolicy::None)
+            ],
+            None
+        );
+        add_to_phase_allowlist(deps.as_mut(), "OG", &["addr1"]);
+        add_to_phase_allowlist(deps.as_mut(), "WL", &["addr2"]);
+        STATE.update(deps.as_mut().storage, |mut state| -> cosmwasm_std::StdResult<_> {
+            state.token_info.denom = Some("sale_token".to_string());
+            state.token_info.decimals = Some(6);
+            Ok(state)
+        }).unwrap();
+        fund_sale(deps.as_mut(), 5000 * 1000000).unwrap();
+
+        let deposit = |address: &str, amount: u64, env: Env, deps: DepsMut| {
+            let info = mock_info(address, &coins((amount as u128) * PRICE, "inj"));
+            execute(deps, env, info, deposit_msg(amount))
+        };
+        deposit("addr1", 100, mock_env(), deps.as_mut()).unwrap();
+        let err = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), ExecuteMsg::RolloverPhases {});
+        assert_eq!(err.unwrap_err(), ContractError::NothingToRollOver {});
+
+        // The first deposit after OG ended carries its unsold supply and allowlist into WL
+        let res = deposit("addr1", 1, env_at(150), deps.as_mut()).unwrap();
+        assert_eq!(
+            res.events,
+            vec![
+                Event::new("rollover")
+                    .add_attribute("phase", "OG")
+                    .add_attribute("policy", "carry_supply_and_allowlist")
+                    .add_attribute("unsold", "900")
+                    .add_attribute("next_phase", "WL")
+            ]
+        );
+        let state = STATE.load(deps.as_ref().storage).unwrap();
+        assert_eq!((state.phases[0].supply, state.phases[1].supply), (100, 1900));
+        assert_eq!(state.phases[1].inherited_allowlists, vec!["OG".to_string()]);
+        let err = deposit("addr3", 1, env_at(150), deps.as_mut()).unwrap_err();
+        assert_eq!(err, ContractError::NotInPhase { phase: "WL".to_string() });
+
+        // Deposits leave returned supply alone, RolloverPhases sends the unsold WL tokens back
+        let res = deposit("addr5", 1, env_at(250), deps.as_mut()).unwrap();
+        assert!(res.messages.is_empty() && res.events.is_empty());
+        let res = query(deps.as_ref(), env_at(250), QueryMsg::GetPhase { phase_name: "WL".to_string() });
+        assert_eq!(from_json::<PhaseInfoResponse>(&res.unwrap()).unwrap().supply, 1900);
+        mock_sale_token_balance(&mut deps, 5000 * 1000000);
+        let msg = ExecuteMsg::RolloverPhases {};
+        let res = execute(deps.as_mut(), env_at(250), mock_info("anyone", &[]), msg).unwrap();
+        assert_eq!(cw20_transfer_amount(&res), Uint128::new(1899 * 1000000));
+        assert_eq!(res.events[0].attributes[1].value, "return_to_project");
+        let state = STATE.load(deps.as_ref().storage).unwrap();
+        assert_eq!(state.token_info.supply, 5000 - 1899);
+        assert_eq!(state.escrowed, Uint128::new(3101 * 1000000));
+        assert_eq!(state.phases[2].supply, 1000);
+    }
+
+    #[test]
+    fn rollover_after_withdraw_unsold_test() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let start = mock_env().block.time.seconds();
+        let phase = Phase {
+            rollover: RolloverPolicy::ReturnToProject,
+            ..public_phase("Public", start, start + 600, 1000)
+        };
+        instantiate_launchpad(deps.as_mut(), vec![phase], None);
+        STATE.update(deps.as_mut().storage, |mut state| -> cosmwasm_std::StdResult<_> {
+            state.token_info.denom = Some("sale_token".to_string());
+            state.token_info.decimals = Some(6);
+            Ok(state)
+        }).unwrap();
+        fund_sale(deps.as_mut(), 5000 * 1000000).unwrap();
+        let info = mock_info("addr1", &coins(10 * PRICE, "inj"));
+        execute(deps.as_mut(), mock_env(), info, deposit_msg(10)).unwrap();
+
+        // The unsold tokens are withdrawn before the rollover applies
+        let ended = env_at(601);
+        mock_sale_token_balance(&mut deps, 5000 * 1000000);
+        let msg = ExecuteMsg::WithdrawUnsold {};
+        let res = execute(deps.as_mut(), ended.clone(), mock_info("admin", &[]), msg).unwrap();
+        assert_eq!(cw20_transfer_amount(&res), Uint128::new(4990 * 1000000));
+
+        // The unsold supply left the sale, Finalize has nothing left to return
+        mock_sale_token_balance(&mut deps, 10 * 1000000);
+        let res = execute(deps.as_mut(), ended, mock_info("admin", &[]), ExecuteMsg::Finalize {}).unwrap();
+        assert!(res.messages.is_empty() && res.events.is_empty());
+        let state = STATE.load(deps.as_ref().storage).unwrap();
+        assert_eq!(state.escrowed, Uint128::new(10 * 1000000));
     }
 }
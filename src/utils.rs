@@ -8,16 +8,27 @@ use cosmwasm_std::{
     BankQuery,
     Coin,
     CosmosMsg,
+    CustomQuery,
     QuerierWrapper,
     QueryRequest,
     StdResult,
+    Storage,
     Uint128,
     WasmMsg,
     WasmQuery,
 };
 use cw20::{ BalanceResponse as CW20BalanceResponse, Cw20ExecuteMsg, Cw20QueryMsg };
 
-use crate::state::Phase;
+use crate::state::{
+    Phase,
+    VestingConfig,
+    PHASE_ALLOWLIST,
+    PHASE_ALLOWLIST_GENERATION,
+    PHASE_ALLOWLIST_SIZE,
+};
+
+pub const BASIS_POINTS: u64 = 10_000;
+pub const MAX_FEES_PERCENTAGE: u64 = 1_000;
 
 pub fn current_phase(current_time: u64, phases: Vec<Phase>) -> Option<Phase> {
     for phase in phases.iter() {
@@ -28,25 +39,97 @@ pub fn current_phase(current_time: u64, phases: Vec<Phase>) -> Option<Phase> {
     None
 }
 
-pub fn count_allowed_user_buy(addr: Addr, phases: HashMap<String, Phase>) -> u64 {
+pub fn count_allowed_user_buy(
+    storage: &dyn Storage,
+    addr: Addr,
+    phases: HashMap<String, Phase>
+) -> StdResult<u64> {
     let mut amount = 0u64;
-    for (_name, phase) in phases.iter() {
-        if phase.address_list.contains(&addr) {
-            amount += phase.limit;
-        } else if phase.address_list.len() == 0 {
+    for (name, phase) in phases.iter() {
+        if is_public_phase(storage, phase)? || is_allowlisted(storage, name, &addr)? {
             amount += phase.limit;
         }
     }
 
-    return amount;
+    Ok(amount)
+}
+
+// A phase without allowlisted addresses, merkle root nor signer gating is open to everyone
+pub fn is_public_phase(storage: &dyn Storage, phase: &Phase) -> StdResult<bool> {
+    if phase.merkle_root.is_some() || phase.signature_required {
+        return Ok(false);
+    }
+    Ok(PHASE_ALLOWLIST_SIZE.may_load(storage, phase.name.clone())?.unwrap_or(0) == 0)
+}
+
+// Allowlist entries of the current generation of the phase
+pub fn allowlist_prefix(storage: &dyn Storage, phase_name: &str) -> StdResult<(String, u64)> {
+    let generation = PHASE_ALLOWLIST_GENERATION.may_load(storage, phase_name.to_string())?;
+    Ok((phase_name.to_string(), generation.unwrap_or(0)))
+}
+
+pub fn is_allowlisted(storage: &dyn Storage, phase_name: &str, addr: &Addr) -> StdResult<bool> {
+    let (name, generation) = allowlist_prefix(storage, phase_name)?;
+    Ok(PHASE_ALLOWLIST.has(storage, (name, generation, addr.clone())))
+}
+
+// Restricted phases also admit the allowlists carried over from ended phases
+pub fn is_eligible(storage: &dyn Storage, phase: &Phase, addr: &Addr) -> StdResult<bool> {
+    if is_public_phase(storage, phase)? || is_allowlisted(storage, &phase.name, addr)? {
+        return Ok(true);
+    }
+
+    for name in phase.inherited_allowlists.iter() {
+        if is_allowlisted(storage, name, addr)? {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+// Amount of `total` released at `current_time` given the vesting schedule starting at `tge_time`
+pub fn vested_amount(
+    total: Uint128,
+    vesting: &Option<VestingConfig>,
+    tge_time: u64,
+    current_time: u64
+) -> Uint128 {
+    let vesting = match vesting {
+        Some(vesting) => vesting,
+        None => {
+            return total;
+        }
+    };
+
+    if current_time < tge_time {
+        return Uint128::zero();
+    }
+
+    let tge_amount = total.multiply_ratio(vesting.tge_unlock_percentage, BASIS_POINTS);
+    let elapsed = current_time - tge_time;
+    if elapsed < vesting.cliff {
+        return tge_amount;
+    }
+
+    let mut linear_elapsed = elapsed - vesting.cliff;
+    if linear_elapsed >= vesting.duration {
+        return total;
+    }
+
+    // Only release whole periods when a granularity is configured
+    if let Some(period) = vesting.period.filter(|period| *period > 0) {
+        linear_elapsed -= linear_elapsed % period;
+    }
+
+    tge_amount + (total - tge_amount).multiply_ratio(linear_elapsed, vesting.duration)
 }
 
-pub fn transfer_token_message(
+pub fn transfer_token_message<T>(
     denom: String,
     token_type: String,
     amount: Uint128,
     receiver: Addr
-) -> StdResult<CosmosMsg> {
+) -> StdResult<CosmosMsg<T>> {
     if token_type == "native" {
         Ok(
             (BankMsg::Send {
@@ -73,8 +156,8 @@ pub fn transfer_token_message(
     }
 }
 
-pub fn get_token_amount(
-    querier: QuerierWrapper,
+pub fn get_token_amount<C: CustomQuery>(
+    querier: QuerierWrapper<C>,
     denom: String,
     contract_addr: Addr,
     token_type: String